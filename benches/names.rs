@@ -0,0 +1,68 @@
+//! The cost of converting symbol names of various types and lengths for a lookup.
+//!
+//! Names that are not null-terminated are copied to a buffer on the stack, or to the heap if they
+//! are too long for it, while `CStr` names are used as they are. Run with `cargo bench`; any
+//! arguments are used to filter the benchmarks by name.
+use libloading::StaticLibrary;
+use std::ffi::CString;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const LENGTHS: &[usize] = &[8, 64, 256, 1024];
+
+/// Run `f` repeatedly for about a tenth of a second and print the average time per call.
+fn bench(filter: &[String], name: &str, length: usize, mut f: impl FnMut() -> bool) {
+    let name = format!("{name}/{length}");
+    if !filter.is_empty() && !filter.iter().any(|filter| name.contains(filter.as_str())) {
+        return;
+    }
+    let mut iterations = 1u32;
+    loop {
+        let start = Instant::now();
+        for _ in 0..iterations {
+            black_box(f());
+        }
+        let elapsed = start.elapsed();
+        if elapsed > Duration::from_millis(100) {
+            println!(
+                "{name:<24} {:>10.1} ns/iter",
+                elapsed.as_nanos() as f64 / f64::from(iterations)
+            );
+            return;
+        }
+        iterations *= 2;
+    }
+}
+
+fn main() {
+    // Skip the flags cargo passes, such as `--bench`.
+    let filter: Vec<String> = std::env::args()
+        .skip(1)
+        .filter(|arg| !arg.starts_with("--"))
+        .collect();
+    for &length in LENGTHS {
+        let name = "x".repeat(length);
+        let library = StaticLibrary::new().with_symbol(&name, main as *const _);
+        let cname = CString::new(name.clone()).unwrap();
+        bench(&filter, "get_str", length, || unsafe {
+            library.get::<fn()>(black_box(name.as_str())).is_ok()
+        });
+        bench(&filter, "get_bytes", length, || unsafe {
+            library.get::<fn()>(black_box(name.as_bytes())).is_ok()
+        });
+        bench(&filter, "get_cstr", length, || unsafe {
+            library.get::<fn()>(black_box(cname.as_c_str())).is_ok()
+        });
+        #[cfg(unix)]
+        {
+            let this = libloading::os::unix::Library::this();
+            bench(&filter, "dlsym_str", length, || unsafe {
+                this.get::<fn()>(black_box(name.as_str())).is_err()
+            });
+        }
+        #[cfg(windows)]
+        bench(&filter, "load_library_str", length, || unsafe {
+            libloading::Library::new(black_box(name.as_str())).is_err()
+        });
+    }
+}
@@ -0,0 +1,327 @@
+//! Procedural macros for the [`libloading`](https://docs.rs/libloading) crate.
+//!
+//! These are re-exported by `libloading` when its `macros` feature is enabled; use them from
+//! there.
+#![deny(missing_docs, unreachable_pub, unused)]
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::parse::{Parse, ParseStream};
+use syn::spanned::Spanned;
+use syn::{
+    Attribute, Error, FnArg, ForeignItem, ForeignItemFn, Ident, ItemForeignMod, LitStr, Meta, Pat,
+    Token, Visibility,
+};
+
+/// Generate bindings to the functions of a dynamic library from an `extern` block.
+///
+/// `#[dynamic_library(pub struct Name)]` on an `extern "C" { ... }` block generates a struct
+/// `Name` instead of linking the declared functions. `Name::new` takes a [`Library`] and binds the
+/// functions, which can then be called as methods of `Name` with the same arguments.
+///
+/// Each function can be annotated with `#[dynamic_library(...)]` to control how it is bound:
+///
+/// * By default, the function is looked up by `Name::new`, which returns the error if it is
+///   missing. The method has the same return type as the function.
+/// * `lazy` functions are looked up when the method is first called. The method returns
+///   `Result<R, libloading::Error>`, with the error from the lookup if the function is missing.
+/// * `optional` functions are allowed to be missing. The method returns `Option<R>`, which is
+///   `None` if the function is missing.
+/// * `lazy` and `optional` can be combined.
+///
+/// `#[link_name = "..."]` changes the name of the symbol a function is looked up by. Other
+/// attributes, such as documentation, are applied to the generated methods. The methods are
+/// `unsafe` to call, just like the declared functions.
+///
+/// `Name` keeps the [`Library`] loaded for as long as it exists. It is available from
+/// `Name::library`. `Name::new`, `Name::library` and the methods have the same visibility as
+/// `Name`.
+///
+/// The generated code refers to the `libloading` crate as `::libloading`. If it is available under
+/// a different path, such as when it has been renamed in `Cargo.toml`, give that path with
+/// `#[dynamic_library(pub struct Name, crate = path::to::libloading)]`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use libloading::{dynamic_library, Library};
+/// use std::ffi::{c_char, c_int};
+///
+/// #[dynamic_library(pub struct Zlib)]
+/// extern "C" {
+///     /// The version of zlib.
+///     fn zlibVersion() -> *const c_char;
+///     #[dynamic_library(lazy)]
+///     fn compressBound(source_len: std::ffi::c_ulong) -> std::ffi::c_ulong;
+///     #[dynamic_library(optional)]
+///     #[link_name = "zlibCompileFlags"]
+///     fn compile_flags() -> std::ffi::c_ulong;
+/// }
+///
+/// let zlib = unsafe { Zlib::new(Library::new("libz.so.1").unwrap()).unwrap() };
+/// unsafe {
+///     println!("{:?}", std::ffi::CStr::from_ptr(zlib.zlibVersion()));
+///     println!("{}", zlib.compressBound(1024).unwrap());
+///     println!("{:?}", zlib.compile_flags());
+/// }
+/// ```
+///
+/// [`Library`]: https://docs.rs/libloading/*/libloading/struct.Library.html
+#[proc_macro_attribute]
+pub fn dynamic_library(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let header = syn::parse_macro_input!(attr as Header);
+    let block = syn::parse_macro_input!(item as ItemForeignMod);
+    expand(header, block)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// `pub struct Name, crate = path` in `#[dynamic_library(pub struct Name, crate = path)]`.
+struct Header {
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    name: Ident,
+    /// The path of the `libloading` crate.
+    krate: syn::Path,
+}
+
+impl Parse for Header {
+    fn parse(input: ParseStream) -> syn::Result<Header> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        input.parse::<Token![struct]>()?;
+        let name = input.parse()?;
+        let mut krate = syn::parse_quote!(::libloading);
+        if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            input.parse::<Token![crate]>()?;
+            input.parse::<Token![=]>()?;
+            krate = input.call(syn::Path::parse_mod_style)?;
+            input.parse::<Option<Token![,]>>()?;
+        }
+        Ok(Header {
+            attrs,
+            vis,
+            name,
+            krate,
+        })
+    }
+}
+
+/// How a function is bound, from `#[dynamic_library(lazy, optional)]` on the function.
+#[derive(Default)]
+struct Binding {
+    lazy: bool,
+    optional: bool,
+}
+
+struct Function {
+    attrs: Vec<Attribute>,
+    binding: Binding,
+    name: Ident,
+    symbol: LitStr,
+    args: Vec<(Ident, syn::Type)>,
+    output: syn::ReturnType,
+}
+
+fn parse_function(item: ForeignItemFn) -> syn::Result<Function> {
+    if let Some(variadic) = &item.sig.variadic {
+        return Err(Error::new(
+            variadic.span(),
+            "variadic functions are not supported",
+        ));
+    }
+    if !item.sig.generics.params.is_empty() {
+        return Err(Error::new(
+            item.sig.generics.span(),
+            "generic functions are not supported",
+        ));
+    }
+    let mut binding = Binding::default();
+    let mut symbol = LitStr::new(&item.sig.ident.to_string(), item.sig.ident.span());
+    let mut attrs = Vec::new();
+    for attr in item.attrs {
+        if attr.path().is_ident("dynamic_library") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("lazy") {
+                    binding.lazy = true;
+                    Ok(())
+                } else if meta.path.is_ident("optional") {
+                    binding.optional = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `lazy` or `optional`"))
+                }
+            })?;
+        } else if attr.path().is_ident("link_name") {
+            match &attr.meta {
+                Meta::NameValue(meta) => symbol = syn::parse2(meta.value.to_token_stream())?,
+                _ => return Err(Error::new(attr.span(), "expected `link_name = \"...\"`")),
+            }
+        } else {
+            attrs.push(attr);
+        }
+    }
+    let mut args = Vec::new();
+    for (index, input) in item.sig.inputs.into_iter().enumerate() {
+        let FnArg::Typed(input) = input else {
+            return Err(Error::new(input.span(), "unexpected receiver"));
+        };
+        let name = match &*input.pat {
+            Pat::Ident(pat) => pat.ident.clone(),
+            _ => format_ident!("arg{}", index),
+        };
+        args.push((name, *input.ty));
+    }
+    Ok(Function {
+        attrs,
+        binding,
+        name: item.sig.ident,
+        symbol,
+        args,
+        output: item.sig.output,
+    })
+}
+
+fn expand(header: Header, block: ItemForeignMod) -> syn::Result<TokenStream2> {
+    let krate = &header.krate;
+    let abi = &block.abi;
+    let mut functions = Vec::new();
+    for item in block.items {
+        match item {
+            ForeignItem::Fn(item) => {
+                let name = &item.sig.ident;
+                if name == "new" || name == "library" {
+                    return Err(Error::new(
+                        name.span(),
+                        "`new` and `library` are reserved for the generated methods",
+                    ));
+                }
+                functions.push(parse_function(item)?)
+            }
+            item => {
+                return Err(Error::new(
+                    item.span(),
+                    "only functions are supported in a `dynamic_library`",
+                ))
+            }
+        }
+    }
+
+    let Header {
+        attrs, vis, name, ..
+    } = &header;
+    let mut fields = Vec::new();
+    let mut initialisers = Vec::new();
+    let mut methods = Vec::new();
+    // The locals of the generated code must not clash with the names of the arguments.
+    let library = Ident::new("library", Span::mixed_site());
+    let f = Ident::new("f", Span::mixed_site());
+    for function in &functions {
+        let Function {
+            attrs,
+            binding,
+            name,
+            symbol,
+            args,
+            output,
+        } = function;
+        let arg_names: Vec<_> = args.iter().map(|(name, _)| name).collect();
+        let arg_types: Vec<_> = args.iter().map(|(_, ty)| ty).collect();
+        let fn_type = quote!(unsafe #abi fn(#(#arg_types),*) #output);
+        let symbol = LitStr::new(&format!("{}\0", symbol.value()), symbol.span());
+        let get = quote! {
+            #krate::Library::get::<#fn_type>(&#library, #symbol).map(|#f| *#f)
+        };
+        let return_type = match output {
+            syn::ReturnType::Default => quote!(()),
+            syn::ReturnType::Type(_, ty) => quote!(#ty),
+        };
+        let span = name.span();
+        let (field_type, initialiser, returns, body) = match (binding.lazy, binding.optional) {
+            (false, false) => (
+                fn_type.clone(),
+                quote!(#get?),
+                return_type,
+                quote!((self.#name)(#(#arg_names),*)),
+            ),
+            (false, true) => (
+                quote!(::core::option::Option<#fn_type>),
+                quote!(#get.ok()),
+                quote!(::core::option::Option<#return_type>),
+                quote!(self.#name.map(|#f| #f(#(#arg_names),*))),
+            ),
+            (true, false) => (
+                quote!(::std::sync::OnceLock<#fn_type>),
+                quote!(::std::sync::OnceLock::new()),
+                quote!(::core::result::Result<#return_type, #krate::Error>),
+                quote! {
+                    let #f = match self.#name.get() {
+                        ::core::option::Option::Some(#f) => *#f,
+                        ::core::option::Option::None => {
+                            let #library = &self.library;
+                            let #f = #get?;
+                            *self.#name.get_or_init(|| #f)
+                        }
+                    };
+                    ::core::result::Result::Ok(#f(#(#arg_names),*))
+                },
+            ),
+            (true, true) => (
+                quote!(::std::sync::OnceLock<::core::option::Option<#fn_type>>),
+                quote!(::std::sync::OnceLock::new()),
+                quote!(::core::option::Option<#return_type>),
+                quote! {
+                    let #library = &self.library;
+                    let #f = *self.#name.get_or_init(|| #get.ok());
+                    #f.map(|#f| #f(#(#arg_names),*))
+                },
+            ),
+        };
+        fields.push(quote_spanned!(span=> #name: #field_type));
+        initialisers.push(quote_spanned!(span=> #name: #initialiser));
+        methods.push(quote_spanned! {span=>
+            #(#attrs)*
+            #vis unsafe fn #name(&self, #(#arg_names: #arg_types),*) -> #returns {
+                #body
+            }
+        });
+    }
+
+    let doc = format!(
+        "Functions of a dynamic library, bound by [`{}::new`].",
+        name
+    );
+    let new_doc =
+        "Bind the functions of `library`.\n\nAll functions that are not `lazy` are looked \
+                   up immediately. An error is returned if one of them is missing, unless it is \
+                   `optional`.\n\n# Safety\n\nThe functions exported by `library` must have the \
+                   declared signatures.";
+    Ok(quote! {
+        #[doc = #doc]
+        #(#attrs)*
+        #[allow(non_snake_case)]
+        #vis struct #name {
+            #(#fields,)*
+            library: #krate::Library,
+        }
+
+        #[allow(non_snake_case, clippy::missing_safety_doc, clippy::too_many_arguments)]
+        impl #name {
+            #[doc = #new_doc]
+            #vis unsafe fn new(#library: #krate::Library) -> ::core::result::Result<Self, #krate::Error> {
+                ::core::result::Result::Ok(#name {
+                    #(#initialisers,)*
+                    library: #library,
+                })
+            }
+
+            /// The library the functions are bound from.
+            #vis fn library(&self) -> &#krate::Library {
+                &self.library
+            }
+
+            #(#methods)*
+        }
+    })
+}
@@ -0,0 +1,168 @@
+use libloading::{dynamic_library, Library};
+use std::path::PathBuf;
+
+/// Build the test helpers of `libloading`.
+fn helpers_path() -> PathBuf {
+    static ONCE: std::sync::Once = std::sync::Once::new();
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("libtest_helpers_macros.module");
+    ONCE.call_once(|| {
+        let rustc = std::env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+        let mut cmd = std::process::Command::new(rustc);
+        cmd.arg(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../src/test_helpers.rs"
+        ))
+        .arg("-o")
+        .arg(&path);
+        if let Some(target) = std::env::var_os("TARGET") {
+            cmd.arg("--target").arg(target);
+        }
+        if cfg!(target_env = "msvc") {
+            cmd.arg(concat!(
+                "-Clink-arg=/DEF:",
+                env!("CARGO_MANIFEST_DIR"),
+                "/../tests/ordinals.def"
+            ));
+        }
+        assert!(cmd
+            .status()
+            .expect("could not compile the test helpers!")
+            .success());
+    });
+    path
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct S {
+    a: u64,
+    b: u32,
+    c: u16,
+    d: u8,
+}
+
+#[dynamic_library(pub struct Helpers)]
+extern "C" {
+    /// Returns its argument.
+    fn test_identity_u32(x: u32) -> u32;
+    fn test_identity_struct(x: S) -> S;
+    #[dynamic_library(lazy)]
+    fn test_get_static_u32() -> u32;
+    #[dynamic_library(optional)]
+    fn test_missing_function() -> u32;
+    #[dynamic_library(lazy)]
+    #[link_name = "test_missing_function"]
+    fn lazy_missing(x: u32);
+    #[dynamic_library(lazy, optional)]
+    #[link_name = "test_identity_u32"]
+    fn lazy_identity(x: u32) -> u32;
+    #[dynamic_library(lazy, optional)]
+    #[link_name = "test_missing_function"]
+    fn lazy_optional_missing() -> u32;
+}
+
+mod renamed {
+    pub use ::libloading as inner;
+}
+
+#[dynamic_library(pub struct Renamed, crate = renamed::inner)]
+extern "C" {
+    fn test_identity_u32(x: u32) -> u32;
+}
+
+mod private {
+    #![deny(private_interfaces, unreachable_pub)]
+
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub(crate) struct Value(pub(crate) u32);
+
+    #[libloading::dynamic_library(pub(crate) struct Private)]
+    extern "C" {
+        #[link_name = "test_identity_u32"]
+        fn identity(x: Value) -> Value;
+    }
+}
+
+#[dynamic_library(pub struct ArgumentNames)]
+extern "C" {
+    #[link_name = "test_identity_u32"]
+    fn eager(library: u32) -> u32;
+    #[dynamic_library(optional)]
+    #[link_name = "test_identity_u32"]
+    fn optional(f: u32) -> u32;
+    #[dynamic_library(lazy)]
+    #[link_name = "test_identity_u32"]
+    fn lazy(library: u32) -> u32;
+    #[dynamic_library(lazy, optional)]
+    #[link_name = "test_identity_u32"]
+    fn lazy_optional(f: u32) -> u32;
+}
+
+#[dynamic_library(struct Missing)]
+extern "C" {
+    fn test_identity_u32(x: u32) -> u32;
+    fn test_missing_function();
+}
+
+#[test]
+fn bind_helpers() {
+    unsafe {
+        let helpers = Helpers::new(Library::new(helpers_path()).unwrap()).unwrap();
+        assert_eq!(helpers.test_identity_u32(42), 42);
+        let value = S {
+            a: 1,
+            b: 2,
+            c: 3,
+            d: 4,
+        };
+        assert_eq!(helpers.test_identity_struct(value), value);
+        assert_eq!(helpers.test_get_static_u32().unwrap(), 0);
+        assert_eq!(helpers.test_get_static_u32().unwrap(), 0);
+        assert_eq!(helpers.test_missing_function(), None);
+        assert!(helpers.lazy_missing(1).is_err());
+        assert!(helpers.lazy_missing(1).is_err());
+        assert_eq!(helpers.lazy_identity(7), Some(7));
+        assert_eq!(helpers.lazy_optional_missing(), None);
+        assert!(helpers
+            .library()
+            .get::<unsafe extern "C" fn()>(b"test_identity_u32\0")
+            .is_ok());
+    }
+}
+
+#[test]
+fn missing_eager_function() {
+    unsafe {
+        let library = Library::new(helpers_path()).unwrap();
+        assert!(Missing::new(library).is_err());
+    }
+}
+
+#[test]
+fn crate_path() {
+    unsafe {
+        let renamed = Renamed::new(Library::new(helpers_path()).unwrap()).unwrap();
+        assert_eq!(renamed.test_identity_u32(42), 42);
+    }
+}
+
+#[test]
+fn restricted_visibility() {
+    use private::{Private, Value};
+    unsafe {
+        let private = Private::new(Library::new(helpers_path()).unwrap()).unwrap();
+        assert_eq!(private.identity(Value(42)), Value(42));
+    }
+}
+
+#[test]
+fn argument_names() {
+    unsafe {
+        let names = ArgumentNames::new(Library::new(helpers_path()).unwrap()).unwrap();
+        assert_eq!(names.eager(1), 1);
+        assert_eq!(names.optional(2), Some(2));
+        assert_eq!(names.lazy(3).unwrap(), 3);
+        assert_eq!(names.lazy_optional(4), Some(4));
+    }
+}
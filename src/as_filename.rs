@@ -1,4 +1,5 @@
 use crate::Error;
+#[cfg(feature = "alloc")]
 use alloc::string::String;
 
 pub(crate) trait Sealed {
@@ -21,6 +22,7 @@ pub(crate) trait Sealed {
 /// [`Library`](crate::Library) instances.
 ///
 /// It is currently sealed and cannot be implemented or its methods called by users of this crate.
+/// Filenames which are loaded repeatedly can be converted to a [`Filename`] once instead.
 #[expect(private_bounds)]
 pub trait AsFilename: Sealed {}
 
@@ -31,12 +33,10 @@ impl Sealed for &str {
         self,
         function: impl FnOnce(*const u16) -> Result<R, Error>,
     ) -> Result<R, Error> {
-        let utf16: alloc::vec::Vec<u16> = if crate::util::check_null_bytes(self.as_bytes())? {
-            self.encode_utf16().collect()
-        } else {
-            self.encode_utf16().chain(Some(0)).collect()
-        };
-        function(utf16.as_ptr())
+        crate::util::check_null_bytes(self.as_bytes())?;
+        // Any null is the terminator, which is added back.
+        let utf16 = self.encode_utf16().take_while(|&unit| unit != 0);
+        crate::util::with_terminated(utf16, function)
     }
 
     #[cfg(unix)]
@@ -47,13 +47,14 @@ impl Sealed for &str {
         if crate::util::check_null_bytes(self.as_bytes())? {
             function(self.as_ptr().cast())
         } else {
-            let buffer = crate::util::copy_and_push(self.as_bytes(), 0);
-            function(buffer.as_ptr().cast())
+            crate::util::with_terminated_slice(self.as_bytes(), |name| function(name.cast()))
         }
     }
 }
 
+#[cfg(feature = "alloc")]
 impl AsFilename for &String {}
+#[cfg(feature = "alloc")]
 impl Sealed for &String {
     #[cfg(windows)]
     fn windows_filename<R>(
@@ -72,7 +73,9 @@ impl Sealed for &String {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl AsFilename for String {}
+#[cfg(feature = "alloc")]
 impl Sealed for String {
     #[cfg(windows)]
     fn windows_filename<R>(
@@ -112,12 +115,10 @@ mod std {
         ) -> Result<R, Error> {
             use std::os::windows::ffi::OsStrExt;
             let bytes = self.as_encoded_bytes();
-            let utf16: alloc::vec::Vec<u16> = if crate::util::check_null_bytes(bytes)? {
-                self.encode_wide().collect()
-            } else {
-                self.encode_wide().chain(Some(0)).collect()
-            };
-            function(utf16.as_ptr())
+            crate::util::check_null_bytes(bytes)?;
+            // Any null is the terminator, which is added back.
+            let utf16 = self.encode_wide().take_while(|&unit| unit != 0);
+            crate::util::with_terminated(utf16, function)
         }
 
         #[cfg(unix)]
@@ -129,8 +130,7 @@ mod std {
             if crate::util::check_null_bytes(bytes)? {
                 function(bytes.as_ptr().cast())
             } else {
-                let buffer = crate::util::copy_and_push(bytes, 0);
-                function(buffer.as_ptr().cast())
+                crate::util::with_terminated_slice(bytes, |name| function(name.cast()))
             }
         }
     }
@@ -240,3 +240,92 @@ mod std {
         }
     }
 }
+
+/// A filename which has been checked for null elements and converted to the null-terminated
+/// encoding of the platform ahead of time.
+///
+/// Loading a `&Filename` passes it to the system as it is, without checking, converting or
+/// copying it again.
+///
+/// # Examples
+///
+/// ```no_run
+/// use libloading::{Filename, Library};
+///
+/// let filename = Filename::new("/path/to/awesome.module").unwrap();
+/// for _ in 0..3 {
+///     let library = unsafe { Library::new(&filename) }.unwrap();
+///     library.close().unwrap();
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg(all(feature = "alloc", any(unix, windows)))]
+#[cfg_attr(libloading_docs, doc(cfg(all(feature = "alloc", any(unix, windows)))))]
+pub struct Filename {
+    #[cfg(unix)]
+    name: alloc::ffi::CString,
+    /// The UTF-16 filename, including the null terminator.
+    #[cfg(windows)]
+    name: alloc::vec::Vec<u16>,
+}
+
+#[cfg(all(feature = "alloc", any(unix, windows)))]
+impl Filename {
+    /// Check `filename` for null elements and convert it.
+    ///
+    /// Fails like loading `filename` would, with [`Error::InteriorZeroElements`] if `filename`
+    /// contains a null element anywhere but at its end.
+    pub fn new(filename: impl AsFilename) -> Result<Filename, Error> {
+        #[cfg(unix)]
+        return filename.posix_filename(|name| {
+            Ok(Filename {
+                name: unsafe { core::ffi::CStr::from_ptr(name) }.into(),
+            })
+        });
+        #[cfg(windows)]
+        return filename.windows_filename(|name| {
+            let mut length = 0;
+            while unsafe { *name.add(length) } != 0 {
+                length += 1;
+            }
+            Ok(Filename {
+                name: unsafe { core::slice::from_raw_parts(name, length + 1) }.to_vec(),
+            })
+        });
+    }
+
+    /// The filename given to `dlopen`.
+    #[cfg(unix)]
+    #[cfg_attr(libloading_docs, doc(cfg(unix)))]
+    pub fn as_c_str(&self) -> &core::ffi::CStr {
+        &self.name
+    }
+
+    /// The UTF-16 filename given to `LoadLibraryExW`, without the null terminator.
+    #[cfg(windows)]
+    #[cfg_attr(libloading_docs, doc(cfg(windows)))]
+    pub fn as_wide(&self) -> &[u16] {
+        &self.name[..self.name.len() - 1]
+    }
+}
+
+#[cfg(all(feature = "alloc", any(unix, windows)))]
+impl AsFilename for &Filename {}
+#[cfg(all(feature = "alloc", any(unix, windows)))]
+impl Sealed for &Filename {
+    #[cfg(windows)]
+    fn windows_filename<R>(
+        self,
+        function: impl FnOnce(*const u16) -> Result<R, Error>,
+    ) -> Result<R, Error> {
+        function(self.name.as_ptr())
+    }
+
+    #[cfg(unix)]
+    fn posix_filename<R>(
+        self,
+        function: impl FnOnce(*const core::ffi::c_char) -> Result<R, Error>,
+    ) -> Result<R, Error> {
+        function(self.name.as_ptr())
+    }
+}
@@ -1,7 +1,12 @@
 use crate::Error;
+#[cfg(feature = "alloc")]
 use alloc::ffi::CString;
+#[cfg(feature = "alloc")]
 use alloc::string::String;
+use core::cmp::Ordering;
 use core::ffi::CStr;
+use core::fmt;
+use core::hash::{Hash, Hasher};
 
 pub(crate) trait Sealed {
     fn symbol_name<R>(
@@ -14,6 +19,7 @@ pub(crate) trait Sealed {
 /// up symbols.
 ///
 /// It is currently sealed and cannot be implemented or its methods called by users of this crate.
+/// Names which are looked up repeatedly can be converted to a [`SymbolName`] once instead.
 #[expect(private_bounds)]
 pub trait AsSymbolName: Sealed {}
 
@@ -27,7 +33,9 @@ impl Sealed for &str {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl AsSymbolName for &String {}
+#[cfg(feature = "alloc")]
 impl Sealed for &String {
     fn symbol_name<R>(
         self,
@@ -37,7 +45,9 @@ impl Sealed for &String {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl AsSymbolName for String {}
+#[cfg(feature = "alloc")]
 impl Sealed for String {
     fn symbol_name<R>(
         mut self,
@@ -62,7 +72,9 @@ impl Sealed for &CStr {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl AsSymbolName for &CString {}
+#[cfg(feature = "alloc")]
 impl Sealed for &CString {
     fn symbol_name<R>(
         self,
@@ -72,7 +84,9 @@ impl Sealed for &CString {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl AsSymbolName for CString {}
+#[cfg(feature = "alloc")]
 impl Sealed for CString {
     fn symbol_name<R>(
         self,
@@ -91,8 +105,7 @@ impl Sealed for &[u8] {
         if crate::util::check_null_bytes(self)? {
             function(self.as_ptr().cast())
         } else {
-            let copy = crate::util::copy_and_push(self, 0);
-            function(copy.as_ptr().cast())
+            crate::util::with_terminated_slice(self, |name| function(name.cast()))
         }
     }
 }
@@ -116,3 +129,159 @@ impl<const N: usize> Sealed for &[u8; N] {
         self.as_slice().symbol_name(function)
     }
 }
+
+/// A symbol name which has been checked for null bytes and null-terminated ahead of time.
+///
+/// Looking up a `&SymbolName` passes it to the system as it is, without checking or copying it
+/// again. Names known at compile time can be written with the [`symbol!`](crate::symbol) macro.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[cfg(feature = "alloc")] {
+/// use libloading::{Library, Symbol, SymbolName};
+///
+/// let name = SymbolName::new("awesome_function").unwrap();
+/// unsafe {
+///     let library = Library::new("/path/to/awesome.module").unwrap();
+///     let f: Symbol<extern "C" fn()> = library.get(&name).unwrap();
+///     f();
+/// }
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct SymbolName {
+    name: Repr,
+}
+
+/// The null-terminated string literal `name_with_nul` as a `CStr`.
+///
+/// Panics, at compile time in a constant, if it contains a null byte before the end.
+pub(crate) const fn literal_c_str(name_with_nul: &'static str) -> &'static CStr {
+    match CStr::from_bytes_with_nul(name_with_nul.as_bytes()) {
+        Ok(name) => name,
+        Err(_) => panic!("symbol name contains a null byte"),
+    }
+}
+
+#[derive(Clone)]
+enum Repr {
+    Static(&'static CStr),
+    #[cfg(feature = "alloc")]
+    Owned(CString),
+}
+
+impl SymbolName {
+    /// Check `name` for null bytes and null-terminate it.
+    ///
+    /// Fails like looking up `name` would, with [`Error::InteriorZeroElements`] if `name`
+    /// contains a null byte anywhere but at its end.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(libloading_docs, doc(cfg(feature = "alloc")))]
+    pub fn new(name: impl AsSymbolName) -> Result<SymbolName, Error> {
+        name.symbol_name(|name| {
+            Ok(SymbolName {
+                name: Repr::Owned(unsafe { CStr::from_ptr(name) }.into()),
+            })
+        })
+    }
+
+    /// A symbol name borrowing `name`, such as a `c"name"` literal.
+    pub const fn from_static(name: &'static CStr) -> SymbolName {
+        SymbolName {
+            name: Repr::Static(name),
+        }
+    }
+
+    #[doc(hidden)]
+    pub const fn from_literal(name_with_nul: &'static str) -> SymbolName {
+        SymbolName::from_static(literal_c_str(name_with_nul))
+    }
+
+    /// The name, as a null-terminated string.
+    pub fn as_c_str(&self) -> &CStr {
+        match &self.name {
+            Repr::Static(name) => name,
+            #[cfg(feature = "alloc")]
+            Repr::Owned(name) => name,
+        }
+    }
+}
+
+impl AsRef<CStr> for SymbolName {
+    fn as_ref(&self) -> &CStr {
+        self.as_c_str()
+    }
+}
+
+impl fmt::Debug for SymbolName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_c_str(), f)
+    }
+}
+
+impl PartialEq for SymbolName {
+    fn eq(&self, other: &SymbolName) -> bool {
+        self.as_c_str() == other.as_c_str()
+    }
+}
+
+impl Eq for SymbolName {}
+
+impl PartialOrd for SymbolName {
+    fn partial_cmp(&self, other: &SymbolName) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SymbolName {
+    fn cmp(&self, other: &SymbolName) -> Ordering {
+        self.as_c_str().cmp(other.as_c_str())
+    }
+}
+
+impl Hash for SymbolName {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_c_str().hash(state)
+    }
+}
+
+impl AsSymbolName for &SymbolName {}
+impl Sealed for &SymbolName {
+    fn symbol_name<R>(
+        self,
+        function: impl FnOnce(*const core::ffi::c_char) -> Result<R, Error>,
+    ) -> Result<R, Error> {
+        function(self.as_c_str().as_ptr())
+    }
+}
+
+/// A [`SymbolName`] known at compile time, as a `&'static SymbolName`.
+///
+/// The name is a string literal, which is null-terminated at compile time. Compilation fails if
+/// it contains a null byte.
+///
+/// # Examples
+///
+/// ```no_run
+/// use libloading::{symbol, Library, Symbol, SymbolName};
+///
+/// const AWESOME_FUNCTION: &SymbolName = symbol!("awesome_function");
+///
+/// unsafe {
+///     let library = Library::new("/path/to/awesome.module").unwrap();
+///     let f: Symbol<extern "C" fn()> = library.get(AWESOME_FUNCTION).unwrap();
+///     f();
+/// }
+/// ```
+///
+/// ```compile_fail
+/// let name = libloading::symbol!("awesome\0function");
+/// ```
+#[macro_export]
+macro_rules! symbol {
+    ($name:literal $(,)?) => {{
+        const NAME: &$crate::SymbolName = &$crate::SymbolName::from_literal(concat!($name, "\0"));
+        NAME
+    }};
+}
@@ -0,0 +1,17 @@
+//! The host process of `libloading::remote::RemoteLibrary`.
+//!
+//! Loads the library named by the only argument and serves the requests read from the standard
+//! input, see `libloading::remote::serve`.
+
+fn main() {
+    let mut arguments = std::env::args_os().skip(1);
+    let (Some(filename), None) = (arguments.next(), arguments.next()) else {
+        eprintln!("usage: libloading-host <library>");
+        std::process::exit(2);
+    };
+    // SAFE: loading the library and calling its functions is what this process is for.
+    if let Err(error) = unsafe { libloading::remote::serve(filename) } {
+        eprintln!("libloading-host: {error}");
+        std::process::exit(1);
+    }
+}
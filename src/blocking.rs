@@ -0,0 +1,87 @@
+//! Running blocking loader calls on another thread, for [`Library::new_async`] and
+//! [`Library::get_async`].
+//!
+//! [`Library::new_async`]: crate::Library::new_async
+//! [`Library::get_async`]: crate::Library::get_async
+
+use alloc::sync::Arc;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
+use std::sync::{Mutex, PoisonError};
+
+/// A future that completes when `function` has returned on another thread.
+pub(crate) struct Blocking<T>(Inner<T>);
+
+enum Inner<T> {
+    Thread(Arc<Mutex<State<T>>>),
+    #[cfg(feature = "tokio")]
+    Tokio(tokio::task::JoinHandle<T>),
+}
+
+enum State<T> {
+    Running(Option<Waker>),
+    Done(std::thread::Result<T>),
+    Taken,
+}
+
+/// Run `function` on a thread of the blocking pool of the current Tokio runtime, or on a new
+/// thread otherwise.
+pub(crate) fn spawn<T, F>(function: F) -> Blocking<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    #[cfg(feature = "tokio")]
+    if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+        return Blocking(Inner::Tokio(runtime.spawn_blocking(function)));
+    }
+    let state = Arc::new(Mutex::new(State::Running(None)));
+    let thread_state = Arc::clone(&state);
+    std::thread::Builder::new()
+        .name("libloading".into())
+        .spawn(move || {
+            // A panic is forwarded to the task awaiting the result.
+            let result = catch_unwind(AssertUnwindSafe(function));
+            let mut state = thread_state.lock().unwrap_or_else(PoisonError::into_inner);
+            if let State::Running(Some(waker)) =
+                core::mem::replace(&mut *state, State::Done(result))
+            {
+                waker.wake();
+            }
+        })
+        .expect("could not spawn a thread");
+    Blocking(Inner::Thread(state))
+}
+
+impl<T> Future for Blocking<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        match &mut self.get_mut().0 {
+            Inner::Thread(state) => {
+                let mut state = state.lock().unwrap_or_else(PoisonError::into_inner);
+                match core::mem::replace(&mut *state, State::Taken) {
+                    State::Running(_) => {
+                        *state = State::Running(Some(cx.waker().clone()));
+                        Poll::Pending
+                    }
+                    State::Done(Ok(value)) => Poll::Ready(value),
+                    State::Done(Err(payload)) => resume_unwind(payload),
+                    State::Taken => panic!("`Blocking` polled after completion"),
+                }
+            }
+            #[cfg(feature = "tokio")]
+            Inner::Tokio(handle) => match Pin::new(handle).poll(cx) {
+                Poll::Pending => Poll::Pending,
+                Poll::Ready(Ok(value)) => Poll::Ready(value),
+                Poll::Ready(Err(error)) => match error.try_into_panic() {
+                    Ok(payload) => resume_unwind(payload),
+                    // Blocking tasks are only cancelled when the runtime shuts down.
+                    Err(error) => panic!("{error}"),
+                },
+            },
+        }
+    }
+}
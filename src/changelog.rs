@@ -1,5 +1,126 @@
 //! The change log.
 
+/// Release 0.10.0 (unreleased)
+///
+/// ## Breaking changes
+///
+/// * Added the `alloc` feature, enabled by the `std` feature. Without it, the `alloc` crate is not
+///   used, so builds with `default-features = false` lose the `AsFilename` and `AsSymbolName`
+///   implementations for `String` and `CString`. Names that are not null-terminated are
+///   null-terminated in buffers on the stack instead, failing with the new `Error::NameTooLong`
+///   past 1023 elements, and `dlerror` messages are truncated to 255 bytes. Enable the `alloc`
+///   feature, as in `features = ["alloc"]`, to keep the previous behaviour without `std`.
+///
+/// ## Non-breaking changes
+///
+/// * Added `Library::check_compatible` and `Library::new_checked` (as well as
+///   `os::unix::Library::check_compatible` and `os::unix::Library::open_checked`) on Linux and
+///   Android. These inspect the ELF headers and the required symbol versions of a library file
+///   before it is loaded and report any incompatibilities with the running process as a typed
+///   `Error::Incompatible` rather than an opaque `dlerror` message.
+/// * Added `os::unix::DynamicSection` on Linux and Android for reading the `SONAME`, `NEEDED`,
+///   `RPATH`, `RUNPATH` and flags of a library file without loading it, as well as resolving its
+///   `NEEDED` entries to files by approximating the search of the glibc dynamic loader.
+/// * Added `os::unix::diagnose_load_failure` on Linux and Android. It walks the dependency tree of
+///   a library file and reports, like `ldd`, which dependencies cannot be found and every location
+///   searched for them.
+/// * Added `os::unix::resolve_library_name` and `os::unix::LdSoCache` on Linux and Android. These
+///   find the file the dynamic loader would pick for a bare library name, following glibc's search
+///   order including `/etc/ld.so.cache` (in either format) and the `glibc-hwcaps` subdirectories.
+///   The functions above now accept bare library names too and consult the cache as well,
+///   failing with the new `Error::LibraryNotFound` if a name cannot be found.
+/// * Added the `manifest` module with a standard `#[repr(C)]` `PluginManifest`, the
+///   `plugin_manifest!` macro to export it from a plugin, and `Library::new_with_manifest` to
+///   reject plugins with a mismatched magic, pointer width or ABI version before any of their
+///   other symbols are used. The rejections are reported as `Error::Manifest`. The version of
+///   the compiler a plugin has been built with can be recorded with
+///   `PluginManifest::with_rustc_version`.
+/// * Added `plugin::PluginManager`, which loads every library in a directory in a deterministic
+///   order, calls an optional initialisation function, reports the outcome for each plugin
+///   separately, can reload a single plugin and unloads the plugins in the reverse order.
+/// * Added the `interface!`, `export!` and `import!` macros and the `interface` module. Functions
+///   declared once with `interface!` are implemented by plugins with `export!`, which fails to
+///   compile if the name or signature does not match the declaration, and looked up by hosts
+///   with `import!`.
+/// * Added the `#[dynamic_library]` attribute macro, enabled with the new `macros` feature. It
+///   turns an `extern "C"` block into a struct binding the declared functions from a `Library`,
+///   with eager, `lazy` and `optional` resolution selectable per function. A renamed `libloading`
+///   can be given with `crate = path`.
+/// * Added `LazyLibrary`, which can be constructed in a `static` and loads the library when it is
+///   first used, caching either the library or the error.
+/// * `Error` now implements `Clone`. To make this possible, `Error::Io` holds its source in an
+///   `Arc`.
+/// * Added `Library::leak`, which keeps a library loaded for the rest of the program and returns
+///   a `&'static Library`, so that its symbols are `Symbol<'static, T>`.
+/// * Added `os::unix::Library::pin` on Linux, the counterpart of `os::windows::Library::pin`. It
+///   marks the library `RTLD_NODELETE`. `Library::leak` pins the library on Linux and Windows,
+///   returning the error if that fails.
+/// * Added `Error::DlInfo` and `Error::DlInfoUnknown`.
+/// * Added `Library::get_typed`, which only accepts symbol types implementing the new sealed
+///   `SymbolType` trait, such as function and raw pointers. Types `Library::get` rejects at runtime
+///   with `Error::IncompatibleSize` are rejected at compile time instead.
+/// * Added `Library::get_static`, which returns a `Static` giving `read`, `write` and volatile
+///   access to an exported variable, and `Library::get_atomic`, which returns a reference to an
+///   exported variable of one of the types implementing `AtomicData`. Both check the alignment of
+///   the variable and, on Linux and Android, its size as recorded in the dynamic symbol table,
+///   reporting `Error::SymbolAlignment` and `Error::SymbolSize` respectively.
+/// * Added `Library::get_slice` and `Library::get_cstr`, which return exported arrays as slices
+///   and C strings, using the size of the array recorded in the dynamic symbol table. The size is
+///   looked up with `dladdr1` on glibc and by searching the dynamic symbol table in memory
+///   elsewhere on Linux and Android. Added `Error::UnknownSymbolSize`,
+///   `Error::SymbolSizeNotMultiple` and `Error::SymbolNotNulTerminated`.
+/// * Added `Symbol::call_catch_unwind`, which calls functions of the types implementing the new
+///   `UnwindFn` trait inside `catch_unwind` and returns a panic as `Error::Panicked`, and
+///   `PoisonableLibrary`, which wraps a `Library`, calls its functions in the same way, and
+///   refuses later calls with `Error::Poisoned` once one of them has panicked, until
+///   `PoisonableLibrary::clear_poison` is called.
+/// * Added `os::unix::probe_load` and `os::unix::probe_load_with_symbols`, which load a library
+///   and look up symbols in a forked child process and report whether that succeeded, crashed
+///   with a signal, exited or timed out as a `ProbeOutcome`, without loading anything into the
+///   calling process. They are available on Linux, Android, macOS and the BSDs.
+/// * Added the `remote` feature with `remote::RemoteLibrary`, which loads a library into a
+///   separate host process and calls its functions with integer, floating point and byte buffer
+///   arguments over pipes, so that a crashing library only terminates the host. The host is the
+///   new `libloading-host` binary, which has to be installed separately, or any program calling
+///   `remote::serve`. Functions with up to four arguments can be called if the host runs on
+///   x86_64 or AArch64. Load and lookup errors are returned as the usual `Error` variants; added
+///   `Error::HostExited` and `Error::Remote`.
+/// * Added `Library::new_async` and `Library::get_async`, which load libraries and look up
+///   symbols on another thread and return a future that can be awaited with any executor. With
+///   the new `tokio` feature, the blocking thread pool of the current Tokio runtime is used.
+/// * Added the `tracing` feature. Loading a library, looking up a symbol and closing a library
+///   then emit a `DEBUG` span named after the underlying call (`dlopen`, `dlsym`, `dlclose`,
+///   `LoadLibraryExW`, `GetProcAddress`, `FreeLibrary`) with the filename, flags or symbol name,
+///   the time the call took, and an event reporting its success or error. The feature works
+///   without `std`, in which case the time is not recorded. Closing a library because it was
+///   dropped is traced as well.
+/// * Added the `observer` module. A `LoadObserver` registered with `observer::add_observer` is
+///   called before and after every library load, after every symbol lookup and after every
+///   close, from any thread. It is also told about the errors from closing a dropped library,
+///   which are otherwise ignored.
+/// * Added the `mock` feature and module, on Unix. Filenames registered with `mock::register` are
+///   served by an in-memory loader, with the given symbol addresses and scripted open, lookup and
+///   close failures, so that code using `Library` can be tested without building real libraries.
+///   Other filenames are still loaded by the system loader. The feature is meant to be enabled
+///   in `[dev-dependencies]` only.
+/// * Added `StaticLibrary`, a set of symbols registered from Rust whose `get` returns the same
+///   `Symbol` as `Library::get`, for plugins linked into the program, such as in fully static
+///   builds or under Miri. Both implement the new `SymbolSource` trait for generic code. Missing
+///   symbols are reported as the new `Error::StaticSymbolNotFound`.
+/// * `SymbolSource` is also implemented by `os::unix::Library` and `os::windows::Library`. The
+///   type of the symbols it returns is now the associated type `SymbolSource::Symbol`, and it
+///   gained `get_singlethreaded` for symbols that may be null and `handle` for the raw handle.
+/// * `Library::leak`, `StaticLibrary`, `observer::add_observer` and `Error::Incompatible` require
+///   the new `alloc` feature.
+/// * Names that are not null-terminated, such as `&str` symbol names and Windows filenames, are now
+///   copied to a buffer on the stack rather than the heap, unless they are longer than 383
+///   elements.
+/// * Added `SymbolName` and `Filename`, which check and null-terminate a name once so that it can
+///   be looked up or loaded repeatedly without converting it again. `Filename` stores the name in
+///   the encoding of the platform. The `symbol!` macro produces a `&'static SymbolName` from a
+///   string literal, and rejects names with null bytes at compile time.
+pub mod r0_10_0 {}
+
 /// Release 0.9.0 (2025-11-05)
 ///
 /// ## Breaking changes
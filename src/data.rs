@@ -0,0 +1,189 @@
+use core::fmt;
+use core::marker;
+use core::sync::atomic;
+
+/// A static variable exported by a loaded dynamic library.
+///
+/// Obtained with [`Library::get_static`](crate::Library::get_static). Unlike a
+/// `Symbol<*mut T>`, this cannot be confused with the value of the variable, and the
+/// lifetime `'lib` ensures that the variable is not accessed after the library is unloaded.
+///
+/// The variable may be accessed by the library, or by other code in the process, at any time.
+/// Accessing it is therefore `unsafe`: the caller must ensure that there are no data races. Use
+/// [`Library::get_atomic`](crate::Library::get_atomic) for variables that are accessed
+/// atomically.
+pub struct Static<'lib, T> {
+    pointer: *mut T,
+    pd: marker::PhantomData<&'lib T>,
+}
+
+impl<T> Static<'_, T> {
+    /// # Safety
+    ///
+    /// `pointer` must point to a `T` that is valid for `'lib`.
+    pub(crate) unsafe fn new(pointer: *mut T) -> Self {
+        Static {
+            pointer,
+            pd: marker::PhantomData,
+        }
+    }
+
+    /// The address of the variable.
+    pub fn as_ptr(&self) -> *mut T {
+        self.pointer
+    }
+
+    /// Read the value of the variable.
+    ///
+    /// # Safety
+    ///
+    /// The variable must not be written to concurrently.
+    pub unsafe fn read(&self) -> T
+    where
+        T: Copy,
+    {
+        self.pointer.read()
+    }
+
+    /// Set the value of the variable.
+    ///
+    /// # Safety
+    ///
+    /// The variable must not be read or written to concurrently, and must not be in read-only
+    /// memory, as e.g. `const` variables in C are.
+    pub unsafe fn write(&self, value: T)
+    where
+        T: Copy,
+    {
+        self.pointer.write(value)
+    }
+
+    /// Read the value of the variable with [`read_volatile`](core::ptr::read_volatile).
+    ///
+    /// # Safety
+    ///
+    /// See [`Static::read`].
+    pub unsafe fn read_volatile(&self) -> T
+    where
+        T: Copy,
+    {
+        self.pointer.read_volatile()
+    }
+
+    /// Set the value of the variable with [`write_volatile`](core::ptr::write_volatile).
+    ///
+    /// # Safety
+    ///
+    /// See [`Static::write`].
+    pub unsafe fn write_volatile(&self, value: T)
+    where
+        T: Copy,
+    {
+        self.pointer.write_volatile(value)
+    }
+}
+
+impl<T> Clone for Static<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Static<'_, T> {}
+
+impl<T> fmt::Debug for Static<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_fmt(format_args!("Static@{:p}", self.pointer))
+    }
+}
+
+unsafe impl<T: Send> Send for Static<'_, T> {}
+unsafe impl<T: Sync> Sync for Static<'_, T> {}
+
+pub(crate) trait Sealed {}
+
+/// This trait is implemented for the atomic types in [`core::sync::atomic`], which can be used
+/// with [`Library::get_atomic`](crate::Library::get_atomic).
+///
+/// It is currently sealed and cannot be implemented by users of this crate.
+#[expect(private_bounds)]
+pub trait AtomicData: Sealed + Sync {}
+
+macro_rules! impl_atomic_data {
+    ($($width:literal => $($ty:ty),*;)*) => {$($(
+        #[cfg(target_has_atomic = $width)]
+        impl AtomicData for $ty {}
+        #[cfg(target_has_atomic = $width)]
+        impl Sealed for $ty {}
+    )*)*};
+}
+
+impl_atomic_data! {
+    "8" => atomic::AtomicBool, atomic::AtomicI8, atomic::AtomicU8;
+    "16" => atomic::AtomicI16, atomic::AtomicU16;
+    "32" => atomic::AtomicI32, atomic::AtomicU32;
+    "64" => atomic::AtomicI64, atomic::AtomicU64;
+    "ptr" => atomic::AtomicIsize, atomic::AtomicUsize;
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl<T> AtomicData for atomic::AtomicPtr<T> {}
+#[cfg(target_has_atomic = "ptr")]
+impl<T> Sealed for atomic::AtomicPtr<T> {}
+
+/// Check that the data symbol at `pointer` can hold a `T`.
+///
+/// The alignment is always checked. The size is checked against the size recorded in the dynamic
+/// symbol table, on platforms where it is available.
+pub(crate) fn check_data_symbol<T>(pointer: *mut T) -> Result<(), crate::Error> {
+    if !pointer.is_aligned() {
+        return Err(crate::Error::SymbolAlignment {
+            expected: align_of::<T>(),
+        });
+    }
+    if let Some(found) = symbol_size(pointer.cast()) {
+        if found != size_of::<T>() {
+            return Err(crate::Error::SymbolSize {
+                found,
+                expected: size_of::<T>(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// View the data symbol at `pointer` as a slice, with a length derived from the size recorded in
+/// the dynamic symbol table.
+///
+/// # Safety
+///
+/// `pointer` must point to an array of `T` that is not modified during `'lib`.
+pub(crate) unsafe fn slice_symbol<'lib, T>(pointer: *const T) -> Result<&'lib [T], crate::Error> {
+    if size_of::<T>() == 0 {
+        return Err(crate::Error::IncompatibleSize);
+    }
+    if !pointer.is_aligned() {
+        return Err(crate::Error::SymbolAlignment {
+            expected: align_of::<T>(),
+        });
+    }
+    let size = symbol_size(pointer.cast()).ok_or(crate::Error::UnknownSymbolSize)?;
+    if size % size_of::<T>() != 0 {
+        return Err(crate::Error::SymbolSizeNotMultiple {
+            found: size,
+            element: size_of::<T>(),
+        });
+    }
+    Ok(core::slice::from_raw_parts(pointer, size / size_of::<T>()))
+}
+
+/// The size of the data symbol starting at `address`, if the platform records it.
+pub(crate) fn symbol_size(address: *const core::ffi::c_void) -> Option<usize> {
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    return crate::os::unix::symbol_size(address);
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    {
+        let _ = address;
+        None
+    }
+}
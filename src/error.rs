@@ -1,20 +1,59 @@
+use crate::manifest::AbiVersion;
+#[cfg(feature = "alloc")]
 use alloc::ffi::CString;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
 use core::ffi::CStr;
 
 /// A `dlerror` error.
-pub struct DlError(pub(crate) CString);
+#[derive(Clone)]
+pub struct DlError(pub(crate) Message);
+
+#[cfg(feature = "alloc")]
+pub(crate) type Message = CString;
+
+/// The capacity of the buffer a `dlerror` message is stored in without the `alloc` feature,
+/// including the null terminator. Longer messages are truncated.
+#[cfg(not(feature = "alloc"))]
+const MESSAGE_CAPACITY: usize = 256;
+
+/// A null-terminated message stored inline, for use without the `alloc` feature.
+#[cfg(not(feature = "alloc"))]
+#[derive(Clone)]
+pub(crate) struct Message([u8; MESSAGE_CAPACITY]);
+
+#[cfg(not(feature = "alloc"))]
+impl core::ops::Deref for Message {
+    type Target = CStr;
+    fn deref(&self) -> &CStr {
+        CStr::from_bytes_until_nul(&self.0).expect("the message is null-terminated")
+    }
+}
+
+#[cfg(not(feature = "alloc"))]
+impl From<&CStr> for Message {
+    fn from(value: &CStr) -> Self {
+        let bytes = value.to_bytes();
+        let length = bytes.len().min(MESSAGE_CAPACITY - 1);
+        let mut buffer = [0; MESSAGE_CAPACITY];
+        buffer[..length].copy_from_slice(&bytes[..length]);
+        Message(buffer)
+    }
+}
 
 impl core::error::Error for DlError {}
 
 impl core::fmt::Debug for DlError {
     fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
-        core::fmt::Debug::fmt(&self.0, f)
+        core::fmt::Debug::fmt(&*self.0, f)
     }
 }
 
 impl core::fmt::Display for DlError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        f.write_str(&self.0.to_string_lossy())
+        crate::util::write_lossy(f, self.0.to_bytes())
     }
 }
 
@@ -48,8 +87,155 @@ impl core::fmt::Display for WindowsError {
     }
 }
 
+/// A reason for a library being incompatible with the running process.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Incompatibility {
+    /// The library is built for a different word size (`EI_CLASS`).
+    Class {
+        /// The class of the library.
+        found: u8,
+        /// The class of the running process.
+        expected: u8,
+    },
+    /// The library is built for a different byte order (`EI_DATA`).
+    Endianness {
+        /// The byte order of the library.
+        found: u8,
+        /// The byte order of the running process.
+        expected: u8,
+    },
+    /// The library is built for a different machine architecture (`e_machine`).
+    Machine {
+        /// The machine of the library.
+        found: u16,
+        /// The machine of the running process.
+        expected: u16,
+    },
+    /// The library is built for an unsupported operating system ABI (`EI_OSABI`).
+    OsAbi {
+        /// The OS ABI of the library.
+        found: u8,
+    },
+    /// The library requires a symbol version not provided by its already loaded dependency.
+    MissingVersion {
+        /// The dependency expected to provide the version, e.g. `libc.so.6`.
+        file: String,
+        /// The version name, e.g. `GLIBC_2.34`.
+        version: String,
+    },
+}
+
+#[cfg(feature = "alloc")]
+impl core::fmt::Display for Incompatibility {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        use Incompatibility::*;
+        match self {
+            Class { found, expected } => {
+                write!(f, "ELF class {found} does not match expected {expected}")
+            }
+            Endianness { found, expected } => {
+                write!(f, "ELF data encoding {found} does not match expected {expected}")
+            }
+            Machine { found, expected } => {
+                write!(f, "ELF machine {found} does not match expected {expected}")
+            }
+            OsAbi { found } => write!(f, "ELF OS ABI {found} is not supported"),
+            MissingVersion { file, version } => {
+                write!(f, "version `{version}` required from `{file}` is not available")
+            }
+        }
+    }
+}
+
+/// A library was found to be incompatible with the running process before loading it.
+#[cfg(feature = "alloc")]
+#[derive(Clone)]
+pub struct IncompatibleLibrary(pub(crate) Vec<Incompatibility>);
+
+#[cfg(feature = "alloc")]
+impl IncompatibleLibrary {
+    /// All the reasons the library is incompatible.
+    pub fn incompatibilities(&self) -> &[Incompatibility] {
+        &self.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl core::error::Error for IncompatibleLibrary {}
+
+#[cfg(feature = "alloc")]
+impl core::fmt::Debug for IncompatibleLibrary {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        core::fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl core::fmt::Display for IncompatibleLibrary {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (index, incompatibility) in self.0.iter().enumerate() {
+            if index != 0 {
+                f.write_str("; ")?;
+            }
+            core::fmt::Display::fmt(incompatibility, f)?;
+        }
+        Ok(())
+    }
+}
+
+/// A plugin was rejected by [`PluginManifest::check`](crate::manifest::PluginManifest::check).
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ManifestError {
+    /// The manifest does not start with [`PluginManifest::MAGIC`], so the symbol is not a
+    /// manifest, or the plugin has been built for a target with a different byte order.
+    ///
+    /// [`PluginManifest::MAGIC`]: crate::manifest::PluginManifest::MAGIC
+    BadMagic {
+        /// The magic found in the manifest.
+        found: u64,
+    },
+    /// The plugin has been built for a target with a different pointer width.
+    PointerWidth {
+        /// The pointer width of the plugin, in bits.
+        found: u32,
+        /// The pointer width of the running process, in bits.
+        expected: u32,
+    },
+    /// The plugin implements a version of the interface the host does not support.
+    IncompatibleAbi {
+        /// The version implemented by the plugin.
+        found: AbiVersion,
+        /// The version implemented by the host.
+        expected: AbiVersion,
+    },
+}
+
+impl core::error::Error for ManifestError {}
+
+impl core::fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ManifestError::BadMagic { found } => {
+                write!(f, "plugin manifest has an unexpected magic {found:#018x}")
+            }
+            ManifestError::PointerWidth { found, expected } => write!(
+                f,
+                "plugin is built for {found}-bit pointers, expected {expected}-bit"
+            ),
+            ManifestError::IncompatibleAbi { found, expected } => write!(
+                f,
+                "plugin implements ABI version {found}, which is not supported by ABI version \
+                 {expected}"
+            ),
+        }
+    }
+}
+
 /// Errors.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 #[non_exhaustive]
 pub enum Error {
     /// The `dlopen` call failed.
@@ -73,6 +259,13 @@ pub enum Error {
     },
     /// The `dlclose` call failed and system did not report an error.
     DlCloseUnknown,
+    /// The `dlinfo` call failed.
+    DlInfo {
+        /// The source error.
+        source: DlError,
+    },
+    /// The `dlinfo` call failed and system did not report an error.
+    DlInfoUnknown,
     /// The `LoadLibraryW` call failed.
     LoadLibraryExW {
         /// The source error.
@@ -103,8 +296,121 @@ pub enum Error {
     FreeLibraryUnknown,
     /// The requested type cannot possibly work.
     IncompatibleSize,
+    /// The size of a data symbol differs from the size of the requested type.
+    SymbolSize {
+        /// The size of the symbol.
+        found: usize,
+        /// The size of the requested type.
+        expected: usize,
+    },
+    /// The address of a data symbol is not aligned for the requested type.
+    SymbolAlignment {
+        /// The alignment of the requested type.
+        expected: usize,
+    },
+    /// The size of a data symbol is not known.
+    ///
+    /// Either the symbol is not in a dynamic symbol table, or the platform does not provide a way
+    /// to look up its size.
+    UnknownSymbolSize,
+    /// The size of a data symbol is not a multiple of the size of the requested element type.
+    SymbolSizeNotMultiple {
+        /// The size of the symbol.
+        found: usize,
+        /// The size of the requested element type.
+        element: usize,
+    },
+    /// A data symbol requested as a C string does not contain a null byte.
+    SymbolNotNulTerminated,
     /// Input symbol of filename contains interior 0/null elements.
     InteriorZeroElements,
+    /// Reading the library file failed.
+    #[cfg(feature = "std")]
+    #[cfg_attr(libloading_docs, doc(cfg(feature = "std")))]
+    Io {
+        /// The source error.
+        ///
+        /// It is shared so that the error can be cloned.
+        source: alloc::sync::Arc<std::io::Error>,
+    },
+    /// The library file is not a well-formed object file.
+    MalformedObject,
+    /// The dynamic loader cache is malformed or built for a different byte order.
+    MalformedLdCache,
+    /// A library name without a `/` was not found in any of the locations the dynamic loader
+    /// searches.
+    #[cfg(feature = "std")]
+    #[cfg_attr(libloading_docs, doc(cfg(feature = "std")))]
+    LibraryNotFound {
+        /// The name of the library.
+        name: std::ffi::OsString,
+    },
+    /// The library file is not compatible with the running process.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(libloading_docs, doc(cfg(feature = "alloc")))]
+    Incompatible {
+        /// The source error.
+        source: IncompatibleLibrary,
+    },
+    /// The plugin manifest of the library has been rejected.
+    Manifest {
+        /// The source error.
+        source: ManifestError,
+    },
+    /// A function called with [`Symbol::call_catch_unwind`] panicked.
+    ///
+    /// [`Symbol::call_catch_unwind`]: crate::Symbol::call_catch_unwind
+    #[cfg(feature = "std")]
+    #[cfg_attr(libloading_docs, doc(cfg(feature = "std")))]
+    Panicked {
+        /// The panic message, or `Box<dyn Any>` if the panic payload is not a string.
+        message: alloc::string::String,
+    },
+    /// The library has been poisoned by a panic, see [`PoisonableLibrary`].
+    ///
+    /// [`PoisonableLibrary`]: crate::PoisonableLibrary
+    #[cfg(feature = "std")]
+    #[cfg_attr(libloading_docs, doc(cfg(feature = "std")))]
+    Poisoned,
+    /// The host process of a [`RemoteLibrary`] has exited, e.g. because the library crashed it.
+    ///
+    /// [`RemoteLibrary`]: crate::remote::RemoteLibrary
+    #[cfg(feature = "remote")]
+    #[cfg_attr(libloading_docs, doc(cfg(feature = "remote")))]
+    HostExited {
+        /// The exit status of the host process.
+        status: std::process::ExitStatus,
+    },
+    /// The host process of a [`RemoteLibrary`] reported an error that has no other
+    /// representation, or the messages exchanged with it are malformed.
+    ///
+    /// [`RemoteLibrary`]: crate::remote::RemoteLibrary
+    #[cfg(feature = "remote")]
+    #[cfg_attr(libloading_docs, doc(cfg(feature = "remote")))]
+    Remote {
+        /// The description of the error.
+        message: alloc::string::String,
+    },
+    /// The initialisation function of a plugin reported a failure.
+    PluginInit {
+        /// The value returned by the initialisation function.
+        status: core::ffi::c_int,
+    },
+    /// No symbol with the name is registered with a [`StaticLibrary`].
+    ///
+    /// [`StaticLibrary`]: crate::StaticLibrary
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(libloading_docs, doc(cfg(feature = "alloc")))]
+    StaticSymbolNotFound {
+        /// The name of the symbol.
+        name: CString,
+    },
+    /// A name to be passed to the system is too long to be null-terminated in a buffer on the
+    /// stack, which is used instead of allocating without the `alloc` feature.
+    NameTooLong {
+        /// The maximum length of a name, in elements.
+        maximum: usize,
+    },
 }
 
 impl core::error::Error for Error {
@@ -115,16 +421,39 @@ impl core::error::Error for Error {
             | GetModuleHandleExW { source }
             | GetProcAddress { source }
             | FreeLibrary { source } => Some(source),
-            DlOpen { source } | DlSym { source } | DlClose { source } => Some(source),
+            DlOpen { source } | DlSym { source } | DlClose { source } | DlInfo { source } => {
+                Some(source)
+            }
+            #[cfg(feature = "std")]
+            Io { source } => Some(&**source),
+            #[cfg(feature = "alloc")]
+            Incompatible { source } => Some(source),
+            Manifest { source } => Some(source),
             DlOpenUnknown
             | DlSymUnknown
             | DlCloseUnknown
+            | DlInfoUnknown
             | LoadLibraryExWUnknown
             | GetModuleHandleExWUnknown
             | GetProcAddressUnknown
             | FreeLibraryUnknown
             | IncompatibleSize
-            | InteriorZeroElements => None,
+            | SymbolSize { .. }
+            | SymbolAlignment { .. }
+            | UnknownSymbolSize
+            | SymbolSizeNotMultiple { .. }
+            | SymbolNotNulTerminated
+            | InteriorZeroElements
+            | MalformedObject
+            | MalformedLdCache
+            | PluginInit { .. }
+            | NameTooLong { .. } => None,
+            #[cfg(feature = "alloc")]
+            StaticSymbolNotFound { .. } => None,
+            #[cfg(feature = "std")]
+            LibraryNotFound { .. } | Panicked { .. } | Poisoned => None,
+            #[cfg(feature = "remote")]
+            HostExited { .. } | Remote { .. } => None,
         }
     }
 }
@@ -139,6 +468,8 @@ impl core::fmt::Display for Error {
             DlSymUnknown => write!(f, "dlsym failed, but system did not report the error"),
             DlClose { .. } => write!(f, "dlclose failed"),
             DlCloseUnknown => write!(f, "dlclose failed, but system did not report the error"),
+            DlInfo { .. } => write!(f, "dlinfo failed"),
+            DlInfoUnknown => write!(f, "dlinfo failed, but system did not report the error"),
             LoadLibraryExW { .. } => write!(f, "LoadLibraryExW failed"),
             LoadLibraryExWUnknown => write!(
                 f,
@@ -160,6 +491,47 @@ impl core::fmt::Display for Error {
             }
             InteriorZeroElements => write!(f, "interior zero element in parameter"),
             IncompatibleSize => write!(f, "requested type cannot possibly work"),
+            SymbolSize { found, expected } => write!(
+                f,
+                "symbol has size {found}, but the requested type has size {expected}"
+            ),
+            SymbolAlignment { expected } => write!(
+                f,
+                "symbol is not aligned to {expected} bytes as the requested type requires"
+            ),
+            UnknownSymbolSize => write!(f, "size of the symbol is not known"),
+            SymbolSizeNotMultiple { found, element } => write!(
+                f,
+                "symbol has size {found}, which is not a multiple of the element size {element}"
+            ),
+            SymbolNotNulTerminated => write!(f, "symbol does not contain a null byte"),
+            #[cfg(feature = "std")]
+            Io { .. } => write!(f, "could not read the library file"),
+            MalformedObject => write!(f, "library file is not a well-formed object file"),
+            MalformedLdCache => write!(f, "dynamic loader cache is malformed"),
+            #[cfg(feature = "std")]
+            LibraryNotFound { ref name } => write!(f, "library {name:?} could not be found"),
+            #[cfg(feature = "alloc")]
+            Incompatible { .. } => {
+                write!(f, "library file is not compatible with the running process")
+            }
+            Manifest { .. } => write!(f, "plugin manifest has been rejected"),
+            PluginInit { status } => write!(f, "plugin initialisation failed with status {status}"),
+            #[cfg(feature = "alloc")]
+            StaticSymbolNotFound { ref name } => {
+                write!(f, "symbol {name:?} is not registered with the static library")
+            }
+            NameTooLong { maximum } => {
+                write!(f, "name is longer than the maximum of {maximum} elements")
+            }
+            #[cfg(feature = "std")]
+            Panicked { ref message } => write!(f, "library function panicked: {message}"),
+            #[cfg(feature = "std")]
+            Poisoned => write!(f, "library has been poisoned by a panic"),
+            #[cfg(feature = "remote")]
+            HostExited { status } => write!(f, "library host process exited with {status}"),
+            #[cfg(feature = "remote")]
+            Remote { ref message } => write!(f, "library host process failed: {message}"),
         }
     }
 }
@@ -0,0 +1,203 @@
+//! Typed definitions of the functions exported by plugins.
+//!
+//! Without these, a function exported by a plugin is spelled out twice: once as a
+//! `#[no_mangle] pub extern "C" fn` in the plugin and once as the name and type passed to
+//! [`Library::get`](crate::Library::get) in the host. Nothing checks that the two agree, and if
+//! they do not, calling the function is undefined behaviour.
+//!
+//! Instead, the functions can be declared once with [`interface!`](crate::interface), in a crate
+//! shared by the plugins and the host. Plugins then implement them with
+//! [`export!`](crate::export), which fails to compile if the name or the signature of the
+//! implementation does not match the declaration, and the host looks them up with
+//! [`import!`](crate::import), which uses the declared name and signature.
+//!
+//! # Examples
+//!
+//! In the shared crate:
+//!
+//! ```
+//! libloading::interface! {
+//!     /// The functions every calculator plugin provides.
+//!     pub mod calculator {
+//!         /// Add two numbers.
+//!         fn add(a: i32, b: i32) -> i32;
+//!     }
+//! }
+//! ```
+//!
+//! In a plugin:
+//!
+//! ```
+//! # libloading::interface! { pub mod calculator { fn add(a: i32, b: i32) -> i32; } }
+//! libloading::export! {
+//!     calculator::add => fn add(a: i32, b: i32) -> i32 {
+//!         a + b
+//!     }
+//! }
+//! ```
+//!
+//! In the host:
+//!
+//! ```no_run
+//! # libloading::interface! { pub mod calculator { fn add(a: i32, b: i32) -> i32; } }
+//! let library = unsafe { libloading::Library::new("/path/to/calculator.module").unwrap() };
+//! let add = unsafe { libloading::import!(library, calculator::add).unwrap() };
+//! assert_eq!(add(1, 2), 3);
+//! ```
+
+use crate::{Error, Library, Symbol};
+use core::ffi::CStr;
+
+/// A function exported by plugins, declared with [`interface!`](crate::interface).
+///
+/// # Safety
+///
+/// Symbols called [`Signature::NAME`] exported by plugins must be functions of type
+/// [`Signature::Fn`].
+pub unsafe trait Signature {
+    /// The type of the function.
+    type Fn: Copy;
+    /// The name of the symbol the function is exported as.
+    const NAME: &'static CStr;
+}
+
+/// Look up the function `S` in `library`.
+///
+/// This is what [`import!`](crate::import) expands to.
+///
+/// # Safety
+///
+/// The `library` must export `S` as declared, for example by implementing it with
+/// [`export!`](crate::export).
+pub unsafe fn import<S: Signature>(library: &Library) -> Result<Symbol<'_, S::Fn>, Error> {
+    library.get(S::NAME)
+}
+
+#[doc(hidden)]
+pub const fn name_matches<S: Signature>(name: &str) -> bool {
+    let expected = S::NAME.to_bytes();
+    let name = name.as_bytes();
+    if expected.len() != name.len() {
+        return false;
+    }
+    let mut index = 0;
+    while index < name.len() {
+        if expected[index] != name[index] {
+            return false;
+        }
+        index += 1;
+    }
+    true
+}
+
+#[doc(hidden)]
+pub const fn symbol_name(name_with_nul: &'static str) -> &'static CStr {
+    crate::as_symbol_name::literal_c_str(name_with_nul)
+}
+
+/// Declare the functions exported by plugins.
+///
+/// Generates a module with a type implementing [`Signature`] for every declared function. The
+/// types are named after the functions, and the functions are exported under their names with the
+/// `"C"` ABI. Functions declared `unsafe` are `unsafe` to call from the host.
+///
+/// Types used in the declarations are resolved in the module containing the invocation.
+///
+/// See [the module documentation](crate::interface) for an example.
+///
+/// [`Signature`]: crate::interface::Signature
+#[macro_export]
+macro_rules! interface {
+    (
+        $(#[$module_attr:meta])*
+        $vis:vis mod $module:ident {
+            $(
+                $(#[$attr:meta])*
+                $(unsafe $(@$unsafe:tt)?)? fn $name:ident($($arg:ident : $ty:ty),* $(,)?)
+                    $(-> $ret:ty)?;
+            )*
+        }
+    ) => {
+        $(#[$module_attr])*
+        $vis mod $module {
+            #[allow(unused_imports)]
+            use super::*;
+            $(
+                $(#[$attr])*
+                #[allow(non_camel_case_types)]
+                #[derive(Clone, Copy, Debug)]
+                pub struct $name;
+
+                unsafe impl $crate::interface::Signature for $name {
+                    type Fn = $(unsafe $(@$unsafe)?)? extern "C" fn($($ty),*) $(-> $ret)?;
+                    const NAME: &'static ::core::ffi::CStr =
+                        $crate::interface::symbol_name(concat!(stringify!($name), "\0"));
+                }
+            )*
+        }
+    };
+}
+
+/// Implement a function declared with [`interface!`](crate::interface) in a plugin.
+///
+/// The function is exported with `#[no_mangle]` and the `"C"` ABI. Compilation fails unless the
+/// function has the same name and signature as the declaration.
+///
+/// See [the module documentation](crate::interface) for an example.
+///
+/// ```compile_fail
+/// libloading::interface! { pub mod calculator { fn add(a: i32, b: i32) -> i32; } }
+/// libloading::export! {
+///     // `i64` instead of `i32`.
+///     calculator::add => fn add(a: i64, b: i64) -> i64 {
+///         a + b
+///     }
+/// }
+/// ```
+///
+/// ```compile_fail
+/// libloading::interface! { pub mod calculator { fn add(a: i32, b: i32) -> i32; } }
+/// libloading::export! {
+///     // Exported as `sum` instead of `add`.
+///     calculator::add => fn sum(a: i32, b: i32) -> i32 {
+///         a + b
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! export {
+    ($(
+        $signature:path => $(#[$attr:meta])*
+        $(unsafe $(@$unsafe:tt)?)? fn $name:ident($($arg:ident : $ty:ty),* $(,)?)
+            $(-> $ret:ty)? $body:block
+    )*) => {$(
+        $(#[$attr])*
+        #[unsafe(no_mangle)]
+        pub $(unsafe $(@$unsafe)?)? extern "C" fn $name($($arg: $ty),*) $(-> $ret)? $body
+
+        const _: () = {
+            let _: <$signature as $crate::interface::Signature>::Fn = $name;
+            assert!(
+                $crate::interface::name_matches::<$signature>(stringify!($name)),
+                concat!(
+                    "`", stringify!($name), "` does not have the name of `",
+                    stringify!($signature), "`"
+                ),
+            );
+        };
+    )*};
+}
+
+/// Look up a function declared with [`interface!`](crate::interface) in a library.
+///
+/// `import!(library, path::to::function)` returns a
+/// `Result<Symbol<'_, F>, Error>`, where `F` is the declared type of the function. It must be
+/// used in an `unsafe` block; see [`import`](crate::interface::import) for the requirements.
+///
+/// See [the module documentation](crate::interface) for an example.
+#[macro_export]
+macro_rules! import {
+    ($library:expr, $signature:path $(,)?) => {
+        $crate::interface::import::<$signature>(&$library)
+    };
+}
@@ -0,0 +1,88 @@
+use crate::as_symbol_name::AsSymbolName;
+use crate::{Error, Library, Symbol};
+use std::sync::OnceLock;
+
+/// A dynamic library that is loaded when it is first used.
+///
+/// The library is loaded with [`Library::new`] by the first call to [`LazyLibrary::library`] or
+/// [`LazyLibrary::get`]. The outcome is kept: once loaded, the library stays loaded for as long as
+/// the `LazyLibrary` exists, and if loading fails, the same error is returned to every later
+/// caller without trying to load the library again.
+///
+/// A `LazyLibrary` can be constructed in a `static`, in which case the symbols obtained from it
+/// are `Symbol<'static, T>`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use libloading::{LazyLibrary, Symbol};
+///
+/// static LIBNOTIFY: LazyLibrary = unsafe { LazyLibrary::new("libnotify.so.4") };
+///
+/// fn notify_init() -> Option<Symbol<'static, unsafe extern "C" fn(*const u8) -> i32>> {
+///     unsafe { LIBNOTIFY.get(b"notify_init\0").ok() }
+/// }
+/// ```
+#[cfg_attr(libloading_docs, doc(cfg(all(feature = "std", any(unix, windows)))))]
+pub struct LazyLibrary {
+    filename: &'static str,
+    library: OnceLock<Result<Library, Error>>,
+}
+
+impl LazyLibrary {
+    /// Create a `LazyLibrary` that loads `filename` when it is first used.
+    ///
+    /// See [`Library::new`] for how `filename` is interpreted.
+    ///
+    /// # Safety
+    ///
+    /// The library is loaded with [`Library::new`] when it is first used, so the requirements
+    /// of [`Library::new`] must hold for whichever code happens to use it first.
+    pub const unsafe fn new(filename: &'static str) -> LazyLibrary {
+        LazyLibrary {
+            filename,
+            library: OnceLock::new(),
+        }
+    }
+
+    /// The filename the library is loaded from.
+    pub fn filename(&self) -> &'static str {
+        self.filename
+    }
+
+    /// Load the library if it has not been attempted yet, and return it.
+    ///
+    /// If loading the library failed, now or during an earlier call, the error is returned.
+    pub fn library(&self) -> Result<&Library, Error> {
+        self.library
+            .get_or_init(|| unsafe {
+                // SAFE: the caller of `LazyLibrary::new` has promised that loading the library is
+                // safe.
+                Library::new(self.filename)
+            })
+            .as_ref()
+            .map_err(Clone::clone)
+    }
+
+    /// Get a pointer to a function or static variable by symbol name, loading the library first
+    /// if it has not been attempted yet.
+    ///
+    /// If loading the library failed, now or during an earlier call, the error is returned. See
+    /// [`Library::get`] for details on symbol lookup.
+    ///
+    /// # Safety
+    ///
+    /// See [`Library::get`].
+    pub unsafe fn get<T>(&self, symbol: impl AsSymbolName) -> Result<Symbol<'_, T>, Error> {
+        self.library()?.get(symbol)
+    }
+}
+
+impl core::fmt::Debug for LazyLibrary {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("LazyLibrary")
+            .field("filename", &self.filename)
+            .field("library", &self.library.get())
+            .finish()
+    }
+}
@@ -39,9 +39,12 @@
     any(unix, windows),
     deny(missing_docs, clippy::all, unreachable_pub, unused)
 )]
+// Without `alloc`, error messages are stored inline, which makes `Error` large by design.
+#![cfg_attr(not(feature = "alloc"), allow(clippy::result_large_err))]
 #![cfg_attr(libloading_docs, feature(doc_cfg))]
 #![no_std]
 
+#[cfg(feature = "alloc")]
 extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
@@ -50,19 +53,68 @@ mod as_filename;
 mod as_symbol_name;
 
 pub use as_filename::AsFilename;
-pub use as_symbol_name::AsSymbolName;
+#[cfg(all(feature = "alloc", any(unix, windows)))]
+pub use as_filename::Filename;
+pub use as_symbol_name::{AsSymbolName, SymbolName};
+mod symbol_type;
+pub use symbol_type::SymbolType;
+#[cfg(all(feature = "std", any(unix, windows, libloading_docs)))]
+mod unwind;
+#[cfg(all(feature = "std", any(unix, windows, libloading_docs)))]
+#[cfg_attr(libloading_docs, doc(cfg(all(feature = "std", any(unix, windows)))))]
+pub use unwind::{PoisonableLibrary, UnwindFn};
 
+#[cfg(all(feature = "std", any(unix, windows, libloading_docs)))]
+mod blocking;
 pub mod changelog;
+#[cfg(any(unix, windows, libloading_docs))]
+mod data;
 mod error;
+#[cfg(any(unix, windows, libloading_docs))]
+#[cfg_attr(libloading_docs, doc(cfg(any(unix, windows))))]
+pub mod interface;
+#[cfg(all(feature = "std", any(unix, windows, libloading_docs)))]
+mod lazy;
+pub mod manifest;
+#[cfg(all(feature = "mock", any(unix, libloading_docs)))]
+#[cfg_attr(libloading_docs, doc(cfg(all(feature = "mock", unix))))]
+pub mod mock;
+#[cfg(any(unix, windows, libloading_docs))]
+#[cfg_attr(libloading_docs, doc(cfg(any(unix, windows))))]
+pub mod observer;
 pub mod os;
+#[cfg(all(feature = "std", any(unix, windows, libloading_docs)))]
+#[cfg_attr(libloading_docs, doc(cfg(all(feature = "std", any(unix, windows)))))]
+pub mod plugin;
+#[cfg(all(feature = "remote", any(unix, windows, libloading_docs)))]
+#[cfg_attr(libloading_docs, doc(cfg(all(feature = "remote", any(unix, windows)))))]
+pub mod remote;
 #[cfg(any(unix, windows, libloading_docs))]
 mod safe;
+#[cfg(all(feature = "alloc", any(unix, windows, libloading_docs)))]
+mod static_library;
+#[cfg(any(unix, windows, libloading_docs))]
+mod symbol_source;
+mod trace;
 mod util;
 
 pub use self::error::Error;
 
+#[cfg(any(unix, windows, libloading_docs))]
+pub use self::data::{AtomicData, Static};
 #[cfg(any(unix, windows, libloading_docs))]
 pub use self::safe::{Library, Symbol};
+#[cfg(all(feature = "alloc", any(unix, windows, libloading_docs)))]
+pub use self::static_library::StaticLibrary;
+#[cfg(any(unix, windows, libloading_docs))]
+pub use self::symbol_source::SymbolSource;
+
+#[cfg(all(feature = "std", any(unix, windows, libloading_docs)))]
+pub use self::lazy::LazyLibrary;
+
+#[cfg(feature = "macros")]
+#[cfg_attr(libloading_docs, doc(cfg(feature = "macros")))]
+pub use libloading_macros::dynamic_library;
 
 /// Converts a library name to a filename generally appropriate for use on the system.
 ///
@@ -0,0 +1,219 @@
+//! A standard manifest for plugins to describe the ABI they have been built for.
+//!
+//! A plugin built as a `cdylib` exports a [`PluginManifest`] with the [`plugin_manifest!`] macro,
+//! and the host loads it with [`Library::new_with_manifest`]. This rejects plugins that were not
+//! built for this host before any of their other symbols are used, with a distinct error for each
+//! reason.
+//!
+//! [`plugin_manifest!`]: crate::plugin_manifest
+//! [`Library::new_with_manifest`]: crate::Library::new_with_manifest
+//!
+//! # Examples
+//!
+//! In the plugin:
+//!
+//! ```
+//! libloading::plugin_manifest!(1, 2, 0);
+//! ```
+//!
+//! In the host:
+//!
+//! ```no_run
+//! use libloading::manifest::AbiVersion;
+//! use libloading::Library;
+//!
+//! let plugin = unsafe {
+//!     Library::new_with_manifest("/path/to/plugin.module", AbiVersion::new(1, 3, 0)).unwrap()
+//! };
+//! ```
+
+pub use crate::error::ManifestError;
+use core::ffi::CStr;
+
+/// The version of the interface between a host and its plugins.
+///
+/// This is a semantic version. Hosts support plugins with the same major version and a minor
+/// version not newer than their own; see [`AbiVersion::supports`].
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AbiVersion {
+    /// Incremented on incompatible changes.
+    pub major: u32,
+    /// Incremented on backwards compatible additions.
+    pub minor: u32,
+    /// Incremented on changes that do not affect the interface.
+    pub patch: u32,
+}
+
+impl AbiVersion {
+    /// Construct a version.
+    pub const fn new(major: u32, minor: u32, patch: u32) -> AbiVersion {
+        AbiVersion {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Whether a host implementing this version can use a plugin built for `plugin`.
+    ///
+    /// The major versions must be equal and the plugin's minor version must not be newer than the
+    /// host's, as the plugin may rely on additions the host does not provide. As is usual for
+    /// semantic versions, before 1.0.0 the minor version is treated as the major one, and before
+    /// 0.1.0 the versions must be equal.
+    pub const fn supports(self, plugin: AbiVersion) -> bool {
+        if self.major != plugin.major {
+            false
+        } else if self.major != 0 {
+            plugin.minor <= self.minor
+        } else if self.minor != 0 {
+            self.minor == plugin.minor && plugin.patch <= self.patch
+        } else {
+            self.patch == plugin.patch
+        }
+    }
+}
+
+impl core::fmt::Display for AbiVersion {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// A description of the ABI a plugin has been built for.
+///
+/// The layout of this structure is the same for all targets, so that the fields can be inspected
+/// even when a plugin has been built for a different target.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct PluginManifest {
+    /// Always [`PluginManifest::MAGIC`].
+    pub magic: u64,
+    /// The width of a pointer in bits on the target the plugin has been built for.
+    pub pointer_width: u32,
+    /// The version of the interface the plugin implements.
+    pub abi_version: AbiVersion,
+    /// The version of the compiler the plugin has been built with as given to
+    /// [`PluginManifest::with_rustc_version`], null-padded, or all null if not given. See
+    /// [`PluginManifest::rustc_version`].
+    pub rustc_version: [u8; 64],
+}
+
+impl PluginManifest {
+    /// Identifies a [`PluginManifest`].
+    ///
+    /// A manifest built for a target with a different byte order has a different magic.
+    pub const MAGIC: u64 = u64::from_le_bytes(*b"libloadM");
+
+    /// The name of the symbol [`plugin_manifest!`](crate::plugin_manifest) exports the manifest
+    /// as.
+    pub const SYMBOL: &'static CStr = c"PLUGIN_MANIFEST";
+
+    /// Construct the manifest for a plugin implementing `abi_version` and built for the same
+    /// target as this crate, without a compiler version.
+    pub const fn new(abi_version: AbiVersion) -> PluginManifest {
+        PluginManifest {
+            magic: PluginManifest::MAGIC,
+            pointer_width: usize::BITS,
+            abi_version,
+            rustc_version: [0; 64],
+        }
+    }
+
+    /// Record `version` as the version of the compiler the plugin has been built with, such as the
+    /// output of `rustc --version`.
+    ///
+    /// The version is truncated to 63 bytes.
+    pub const fn with_rustc_version(mut self, version: &str) -> PluginManifest {
+        let version = version.as_bytes();
+        self.rustc_version = [0; 64];
+        let mut index = 0;
+        // The last byte is always left null.
+        while index < version.len() && index < self.rustc_version.len() - 1 {
+            self.rustc_version[index] = version[index];
+            index += 1;
+        }
+        self
+    }
+
+    /// The version of the compiler the plugin has been built with, e.g. `rustc 1.88.0 (6b00bc388
+    /// 2025-06-23)`, as recorded by [`PluginManifest::with_rustc_version`].
+    ///
+    /// This is informational only and is not checked by [`PluginManifest::check`]. `None` if no
+    /// version has been recorded or the field is not valid UTF-8.
+    pub fn rustc_version(&self) -> Option<&str> {
+        let length = self
+            .rustc_version
+            .iter()
+            .position(|&b| b == 0)
+            .unwrap_or(self.rustc_version.len());
+        if length == 0 {
+            return None;
+        }
+        core::str::from_utf8(&self.rustc_version[..length]).ok()
+    }
+
+    /// Check whether a host implementing `host_abi` can use the plugin with this manifest.
+    ///
+    /// The magic is checked first, then the pointer width and finally the ABI version, and the
+    /// first mismatch is reported.
+    pub fn check(&self, host_abi: AbiVersion) -> Result<(), ManifestError> {
+        if self.magic != PluginManifest::MAGIC {
+            return Err(ManifestError::BadMagic { found: self.magic });
+        }
+        if self.pointer_width != usize::BITS {
+            return Err(ManifestError::PointerWidth {
+                found: self.pointer_width,
+                expected: usize::BITS,
+            });
+        }
+        if !host_abi.supports(self.abi_version) {
+            return Err(ManifestError::IncompatibleAbi {
+                found: self.abi_version,
+                expected: host_abi,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Export a [`PluginManifest`] from a plugin.
+///
+/// The arguments are the major, minor and patch components of the [`AbiVersion`] implemented by
+/// the plugin. The manifest is exported as [`PluginManifest::SYMBOL`], which must therefore not
+/// be used for anything else.
+///
+/// The manifest records the pointer width of the target the plugin is being built for. The
+/// version of the compiler can be recorded too, with a `rustc_version` argument; see
+/// [`PluginManifest::with_rustc_version`].
+///
+/// # Examples
+///
+/// ```
+/// libloading::plugin_manifest!(0, 3, 1);
+/// ```
+///
+/// ```
+/// // Such as `env!("PLUGIN_RUSTC_VERSION")`, set by the build script of the plugin.
+/// libloading::plugin_manifest!(0, 3, 1, rustc_version = "rustc 1.88.0 (6b00bc388 2025-06-23)");
+/// ```
+#[macro_export]
+macro_rules! plugin_manifest {
+    ($major:expr, $minor:expr, $patch:expr $(,)?) => {
+        #[unsafe(no_mangle)]
+        #[used]
+        pub static PLUGIN_MANIFEST: $crate::manifest::PluginManifest =
+            $crate::manifest::PluginManifest::new($crate::manifest::AbiVersion::new(
+                $major, $minor, $patch,
+            ));
+    };
+    ($major:expr, $minor:expr, $patch:expr, rustc_version = $version:expr $(,)?) => {
+        #[unsafe(no_mangle)]
+        #[used]
+        pub static PLUGIN_MANIFEST: $crate::manifest::PluginManifest =
+            $crate::manifest::PluginManifest::new($crate::manifest::AbiVersion::new(
+                $major, $minor, $patch,
+            ))
+            .with_rustc_version($version);
+    };
+}
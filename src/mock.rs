@@ -0,0 +1,368 @@
+//! An in-memory dynamic loader for testing code that uses [`Library`](crate::Library).
+//!
+//! With the `mock` feature enabled, [`Library`](crate::Library) and
+//! [`os::unix::Library`](crate::os::unix::Library) first consult the libraries registered with
+//! [`register`]. Opening a registered filename does not touch the filesystem or the system
+//! loader; the symbols of the library resolve to the addresses given to [`MockLibrary::symbol`],
+//! and its scripted failures are reported through the usual [`Error`](crate::Error) variants,
+//! with the scripted message as their source. Filenames that are not registered, and libraries
+//! opened from them, are handled by the system loader as usual.
+//!
+//! The registry is shared by the whole process, so tests running concurrently should register
+//! distinct filenames.
+//!
+//! # Enabling the feature
+//!
+//! The feature replaces the loader of every `Library` in the build, so it must only be enabled
+//! for tests, in `[dev-dependencies]`:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! libloading = { version = "0.9", features = ["mock"] }
+//! ```
+//!
+//! While no library is registered and no mock library is open, every call goes to the system
+//! loader after checking an atomic counter. Otherwise, every call takes a lock shared by the
+//! whole process.
+//!
+//! # Examples
+//!
+//! ```
+//! use libloading::mock::{self, MockLibrary};
+//! use libloading::{Error, Library, Symbol};
+//!
+//! extern "C" fn answer() -> u32 {
+//!     42
+//! }
+//!
+//! mock::register(
+//!     "libanswer.so",
+//!     MockLibrary::new()
+//!         .symbol("answer", answer as *const _)
+//!         .missing_symbol("question", "libanswer.so: undefined symbol: question"),
+//! );
+//! mock::register("libbroken.so", MockLibrary::new().open_error("libbroken.so: bad ELF magic"));
+//!
+//! unsafe {
+//!     let library = Library::new("libanswer.so").unwrap();
+//!     let f: Symbol<extern "C" fn() -> u32> = library.get("answer").unwrap();
+//!     assert_eq!(f(), 42);
+//!     let error = library.get::<extern "C" fn()>("question").unwrap_err();
+//!     assert!(matches!(error, Error::DlSym { .. }));
+//!     let error = Library::new("libbroken.so").unwrap_err();
+//!     assert!(matches!(error, Error::DlOpen { .. }));
+//! }
+//! ```
+
+use crate::as_filename::AsFilename;
+use crate::as_symbol_name::AsSymbolName;
+use crate::os::unix::backend::{Backend, System};
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::ffi::CString;
+use alloc::format;
+use alloc::sync::Arc;
+use core::cell::Cell;
+use core::ffi::{c_char, c_int, c_void, CStr};
+use core::ptr::null_mut;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// A library served by the mock loader, registered with [`register`].
+///
+/// Looking up a symbol that has been neither added with [`symbol`](MockLibrary::symbol) nor
+/// with [`missing_symbol`](MockLibrary::missing_symbol) fails like it would with glibc, with
+/// the message `<filename>: undefined symbol: <name>`.
+#[derive(Clone, Debug, Default)]
+pub struct MockLibrary {
+    symbols: BTreeMap<CString, Result<usize, CString>>,
+    open_error: Option<CString>,
+    close_error: Option<CString>,
+}
+
+impl MockLibrary {
+    /// A library without any symbols, which opens and closes successfully.
+    pub fn new() -> MockLibrary {
+        MockLibrary::default()
+    }
+
+    /// Add a symbol resolving to `address`.
+    ///
+    /// # Panics
+    ///
+    /// If `name` contains a null byte anywhere but at its end.
+    pub fn symbol(mut self, name: impl AsSymbolName, address: *const c_void) -> MockLibrary {
+        self.symbols.insert(owned_name(name), Ok(address as usize));
+        self
+    }
+
+    /// Make looking up the symbol `name` fail with the error `message`.
+    ///
+    /// # Panics
+    ///
+    /// If `name` contains a null byte anywhere but at its end, or `message` contains a null byte.
+    pub fn missing_symbol(mut self, name: impl AsSymbolName, message: &str) -> MockLibrary {
+        self.symbols
+            .insert(owned_name(name), Err(message_string(message)));
+        self
+    }
+
+    /// Make opening the library fail with the error `message`.
+    ///
+    /// # Panics
+    ///
+    /// If `message` contains a null byte.
+    pub fn open_error(mut self, message: &str) -> MockLibrary {
+        self.open_error = Some(message_string(message));
+        self
+    }
+
+    /// Make closing the library fail with the error `message`.
+    ///
+    /// The handle is released nevertheless.
+    ///
+    /// # Panics
+    ///
+    /// If `message` contains a null byte.
+    pub fn close_error(mut self, message: &str) -> MockLibrary {
+        self.close_error = Some(message_string(message));
+        self
+    }
+}
+
+fn owned_name(name: impl AsSymbolName) -> CString {
+    name.symbol_name(|name| Ok(unsafe { CStr::from_ptr(name) }.into()))
+        .expect("symbol name must not contain interior null bytes")
+}
+
+fn message_string(message: &str) -> CString {
+    CString::new(message).expect("error message must not contain null bytes")
+}
+
+/// Serve `filename` from the mock loader with `library`, replacing any library previously
+/// registered under the same filename.
+///
+/// `filename` is compared with the filenames libraries are opened with byte for byte, without
+/// searching any paths. Libraries that are already open are not affected.
+///
+/// # Panics
+///
+/// If `filename` contains a null byte anywhere but at its end.
+pub fn register(filename: impl AsFilename, library: MockLibrary) {
+    let filename = owned_filename(filename);
+    let mut registry = registry();
+    registry.libraries.insert(filename, Arc::new(library));
+    registry.count_entries();
+}
+
+/// Stop serving `filename` from the mock loader, returning whether it was registered.
+///
+/// Libraries that are already open are not affected.
+///
+/// # Panics
+///
+/// If `filename` contains a null byte anywhere but at its end.
+pub fn unregister(filename: impl AsFilename) -> bool {
+    let filename = owned_filename(filename);
+    let mut registry = registry();
+    let removed = registry.libraries.remove(&filename).is_some();
+    registry.count_entries();
+    removed
+}
+
+/// The number of handles to the mock library `filename` which have not been closed yet.
+///
+/// Every successful open returns a new handle.
+///
+/// # Panics
+///
+/// If `filename` contains a null byte anywhere but at its end.
+pub fn open_count(filename: impl AsFilename) -> usize {
+    let filename = owned_filename(filename);
+    registry()
+        .handles
+        .values()
+        .filter(|handle| handle.filename == filename)
+        .count()
+}
+
+fn owned_filename(filename: impl AsFilename) -> CString {
+    filename
+        .posix_filename(|filename| Ok(unsafe { CStr::from_ptr(filename) }.into()))
+        .expect("filename must not contain interior null bytes")
+}
+
+struct Registry {
+    libraries: BTreeMap<CString, Arc<MockLibrary>>,
+    /// The open handles, by their address.
+    handles: BTreeMap<usize, Box<Handle>>,
+}
+
+impl Registry {
+    /// Update [`ENTRIES`] after the registry has been changed.
+    fn count_entries(&self) {
+        ENTRIES.store(self.libraries.len() + self.handles.len(), Ordering::Release);
+    }
+}
+
+struct Handle {
+    filename: CString,
+    library: Arc<MockLibrary>,
+}
+
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry {
+    libraries: BTreeMap::new(),
+    handles: BTreeMap::new(),
+});
+
+/// The number of registered libraries and open handles, so that the registry need not be locked
+/// while it is empty.
+///
+/// A handle can only be passed to the loader after it has been returned by it, so the store that
+/// counted it is always seen.
+static ENTRIES: AtomicUsize = AtomicUsize::new(0);
+
+fn registry() -> MutexGuard<'static, Registry> {
+    REGISTRY.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The registry, or `None` if it is empty and every call goes to the system loader.
+fn nonempty_registry() -> Option<MutexGuard<'static, Registry>> {
+    if ENTRIES.load(Ordering::Acquire) == 0 {
+        None
+    } else {
+        Some(registry())
+    }
+}
+
+std::thread_local! {
+    /// The error of the last failing mock call, not reported by `dlerror` yet.
+    static PENDING_ERROR: Cell<Option<CString>> = const { Cell::new(None) };
+    /// The error last reported by `dlerror`, kept alive until the next one is reported.
+    static REPORTED_ERROR: Cell<Option<CString>> = const { Cell::new(None) };
+}
+
+fn fail(message: CString) {
+    PENDING_ERROR.set(Some(message));
+}
+
+/// Forget the error of the last failing mock call, before a call to the system loader, whose
+/// errors are reported by the system `dlerror` instead.
+fn delegate() {
+    PENDING_ERROR.set(None);
+}
+
+/// Open the mock library `filename`, or return `None` if it is not registered.
+fn open(filename: &CStr) -> Option<Result<*mut c_void, CString>> {
+    let mut registry = nonempty_registry()?;
+    let library = Arc::clone(registry.libraries.get(filename)?);
+    if let Some(message) = &library.open_error {
+        return Some(Err(message.clone()));
+    }
+    let handle = Box::new(Handle {
+        filename: filename.into(),
+        library,
+    });
+    let address = &*handle as *const Handle as *mut c_void;
+    registry.handles.insert(address as usize, handle);
+    registry.count_entries();
+    Some(Ok(address))
+}
+
+/// The backend used with the `mock` feature: the registered libraries, falling back to the
+/// system loader.
+pub(crate) struct Mock;
+
+impl Backend for Mock {
+    unsafe fn dlopen(filename: *const c_char, flags: c_int) -> *mut c_void {
+        let opened = if filename.is_null() {
+            None
+        } else {
+            open(CStr::from_ptr(filename))
+        };
+        match opened {
+            None => {
+                delegate();
+                System::dlopen(filename, flags)
+            }
+            Some(Ok(handle)) => handle,
+            Some(Err(message)) => {
+                fail(message);
+                null_mut()
+            }
+        }
+    }
+
+    unsafe fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void {
+        let result = nonempty_registry().and_then(|registry| {
+            registry.handles.get(&(handle as usize)).map(|handle| {
+                let symbol = CStr::from_ptr(symbol);
+                match handle.library.symbols.get(symbol) {
+                    Some(result) => result.clone(),
+                    None => Err(message_string(&format!(
+                        "{}: undefined symbol: {}",
+                        handle.filename.to_string_lossy(),
+                        symbol.to_string_lossy()
+                    ))),
+                }
+            })
+        });
+        match result {
+            None => {
+                delegate();
+                System::dlsym(handle, symbol)
+            }
+            Some(Ok(address)) => address as *mut c_void,
+            Some(Err(message)) => {
+                fail(message);
+                null_mut()
+            }
+        }
+    }
+
+    unsafe fn dlclose(handle: *mut c_void) -> c_int {
+        let closed = nonempty_registry().and_then(|mut registry| {
+            let closed = registry.handles.remove(&(handle as usize));
+            registry.count_entries();
+            closed
+        });
+        match closed {
+            None => {
+                delegate();
+                System::dlclose(handle)
+            }
+            Some(closed) => match &closed.library.close_error {
+                None => 0,
+                Some(message) => {
+                    fail(message.clone());
+                    -1
+                }
+            },
+        }
+    }
+
+    unsafe fn dlerror() -> *mut c_char {
+        match PENDING_ERROR.take() {
+            None => System::dlerror(),
+            Some(message) => {
+                // The buffer of a `CString` does not move with it.
+                let pointer = message.as_ptr().cast_mut();
+                REPORTED_ERROR.set(Some(message));
+                pointer
+            }
+        }
+    }
+
+    #[cfg(any(target_os = "linux", libloading_docs))]
+    unsafe fn dlinfo(handle: *mut c_void, request: c_int, info: *mut c_void) -> c_int {
+        let is_mock = nonempty_registry()
+            .is_some_and(|registry| registry.handles.contains_key(&(handle as usize)));
+        if is_mock {
+            fail(message_string("dlinfo is not supported for mock libraries"));
+            -1
+        } else {
+            delegate();
+            System::dlinfo(handle, request, info)
+        }
+    }
+}
@@ -0,0 +1,168 @@
+//! Process-wide observation of libraries being loaded, looked up and unloaded.
+//!
+//! A [`LoadObserver`] registered with [`add_observer`] is told about every library loaded, every
+//! symbol looked up and every library closed through this crate, from any thread, including the
+//! errors that the implementation of `Drop` for [`Library`](crate::Library) otherwise ignores.
+//! This makes it possible to collect metrics or raise alerts without wrapping every `Library`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use libloading::observer::LoadObserver;
+//! use libloading::Error;
+//! use std::ffi::c_void;
+//!
+//! struct LogDropErrors;
+//!
+//! impl LoadObserver for LogDropErrors {
+//!     fn after_drop(&self, library: *mut c_void, result: Result<(), &Error>) {
+//!         if let Err(error) = result {
+//!             eprintln!("could not unload library {library:p}: {error}");
+//!         }
+//!     }
+//! }
+//!
+//! # #[cfg(feature = "alloc")]
+//! libloading::observer::add_observer(&LogDropErrors);
+//! ```
+
+use crate::Error;
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+use core::ffi::{c_void, CStr};
+use core::fmt;
+use core::ptr::null_mut;
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+/// Callbacks for the operations of the dynamic loader.
+///
+/// All methods do nothing by default. Libraries are identified by their raw handle, which is the
+/// same in all the callbacks for a library, and symbols by the address they resolved to.
+///
+/// The callbacks run on the thread performing the operation, right before or after it, so they
+/// should be quick. They may load libraries themselves, in which case they are called again for
+/// those.
+pub trait LoadObserver: Sync {
+    /// Called before a library is loaded.
+    fn before_load(&self, name: LibraryName<'_>) {
+        let _ = name;
+    }
+
+    /// Called after a library has been loaded, with its handle, or after loading it failed.
+    fn after_load(&self, name: LibraryName<'_>, result: Result<*mut c_void, &Error>) {
+        let _ = (name, result);
+    }
+
+    /// Called after a symbol has been looked up, with its address, or after the lookup failed.
+    fn after_get(
+        &self,
+        library: *mut c_void,
+        symbol: SymbolKey<'_>,
+        result: Result<*mut c_void, &Error>,
+    ) {
+        let _ = (library, symbol, result);
+    }
+
+    /// Called after a library has been explicitly closed, such as with
+    /// [`Library::close`](crate::Library::close).
+    fn after_close(&self, library: *mut c_void, result: Result<(), &Error>) {
+        let _ = (library, result);
+    }
+
+    /// Called after a library has been closed because it was dropped.
+    ///
+    /// This is the only way to learn about the errors which occur in this case.
+    fn after_drop(&self, library: *mut c_void, result: Result<(), &Error>) {
+        let _ = (library, result);
+    }
+}
+
+/// The name of a library, as given to the system loader.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LibraryName<'a> {
+    /// The program itself, such as with [`os::unix::Library::this`].
+    ///
+    /// [`os::unix::Library::this`]: crate::os::unix::Library::this
+    This,
+    /// A filename given to `dlopen`.
+    Posix(&'a CStr),
+    /// A filename given to `LoadLibraryExW`, without the null terminator.
+    Windows(&'a [u16]),
+}
+
+impl fmt::Display for LibraryName<'_> {
+    /// Formats the name, replacing invalid sequences with `U+FFFD REPLACEMENT CHARACTER`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use fmt::Write;
+        match *self {
+            LibraryName::This => f.write_str("<this program>"),
+            LibraryName::Posix(name) => crate::util::write_lossy(f, name.to_bytes()),
+            LibraryName::Windows(name) => {
+                for c in char::decode_utf16(name.iter().copied()) {
+                    f.write_char(c.unwrap_or(char::REPLACEMENT_CHARACTER))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// How a symbol was looked up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SymbolKey<'a> {
+    /// By name.
+    Name(&'a CStr),
+    /// By ordinal, on Windows.
+    Ordinal(u16),
+}
+
+struct Node {
+    observer: &'static dyn LoadObserver,
+    next: *mut Node,
+}
+
+/// The registered observers, most recently registered first. Nodes are never removed.
+static OBSERVERS: AtomicPtr<Node> = AtomicPtr::new(null_mut());
+
+/// Register an observer for the rest of the lifetime of the process.
+///
+/// Observers cannot be unregistered. Observers that need to be created at runtime can be
+/// leaked with [`Box::leak`].
+///
+/// Each observer is called for the operations that start after this function has returned,
+/// most recently registered observer first.
+#[cfg(feature = "alloc")]
+#[cfg_attr(libloading_docs, doc(cfg(feature = "alloc")))]
+pub fn add_observer(observer: &'static dyn LoadObserver) {
+    let node = Box::into_raw(Box::new(Node {
+        observer,
+        next: null_mut(),
+    }));
+    let mut head = OBSERVERS.load(Ordering::Relaxed);
+    loop {
+        // SAFE: the node is not shared until it has been published below.
+        unsafe { (*node).next = head };
+        match OBSERVERS.compare_exchange_weak(head, node, Ordering::Release, Ordering::Relaxed) {
+            Ok(_) => return,
+            Err(current) => head = current,
+        }
+    }
+}
+
+/// Call `notify` with every registered observer.
+pub(crate) fn notify(mut notify: impl FnMut(&dyn LoadObserver)) {
+    let mut node = OBSERVERS.load(Ordering::Acquire);
+    // SAFE: published nodes are immutable and never freed.
+    while let Some(current) = unsafe { node.as_ref() } {
+        notify(current.observer);
+        node = current.next;
+    }
+}
+
+/// Whether any observer has been registered, for skipping work that is only needed to notify
+/// them.
+pub(crate) fn is_observed() -> bool {
+    !OBSERVERS.load(Ordering::Relaxed).is_null()
+}
@@ -0,0 +1,49 @@
+//! The `dl*` functions used by [`Library`](super::Library), behind a trait so that the `mock`
+//! feature can substitute an in-memory loader.
+
+use core::ffi::{c_char, c_int, c_void};
+
+/// The subset of the `dlfcn.h` API a [`Library`](super::Library) is implemented with.
+///
+/// The functions have the same contract as their C counterparts. In particular, `dlerror`
+/// reports the error of the last failing call on the current thread.
+pub(crate) trait Backend {
+    unsafe fn dlopen(filename: *const c_char, flags: c_int) -> *mut c_void;
+    unsafe fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+    unsafe fn dlclose(handle: *mut c_void) -> c_int;
+    unsafe fn dlerror() -> *mut c_char;
+    #[cfg(any(target_os = "linux", libloading_docs))]
+    unsafe fn dlinfo(handle: *mut c_void, request: c_int, info: *mut c_void) -> c_int;
+}
+
+/// The dynamic loader of the system.
+pub(crate) struct System;
+
+impl Backend for System {
+    unsafe fn dlopen(filename: *const c_char, flags: c_int) -> *mut c_void {
+        super::dlopen(filename, flags)
+    }
+
+    unsafe fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void {
+        super::dlsym(handle, symbol)
+    }
+
+    unsafe fn dlclose(handle: *mut c_void) -> c_int {
+        super::dlclose(handle)
+    }
+
+    unsafe fn dlerror() -> *mut c_char {
+        super::dlerror()
+    }
+
+    #[cfg(any(target_os = "linux", libloading_docs))]
+    unsafe fn dlinfo(handle: *mut c_void, request: c_int, info: *mut c_void) -> c_int {
+        super::dlinfo(handle, request, info)
+    }
+}
+
+/// The backend used by [`Library`](super::Library).
+#[cfg(not(feature = "mock"))]
+pub(crate) type Active = System;
+#[cfg(feature = "mock")]
+pub(crate) type Active = crate::mock::Mock;
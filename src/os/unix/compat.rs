@@ -0,0 +1,194 @@
+use super::dynsym::{dl_iterate_phdr, DlPhdrInfo};
+use super::elf::{ElfFile, Header, ELFCLASS32, ELFCLASS64, ELFDATA2LSB, ELFDATA2MSB};
+use crate::error::Incompatibility;
+use crate::Error;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ffi::{c_int, c_void, CStr};
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+
+/// The result of checking whether a library file can be loaded into the running process.
+///
+/// Obtained via [`Library::check_compatible`](super::Library::check_compatible).
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct CompatibilityReport {
+    /// The `EI_CLASS` of the library: `1` for 32-bit and `2` for 64-bit objects.
+    pub class: u8,
+    /// The `EI_DATA` of the library: `1` for little-endian and `2` for big-endian objects.
+    pub data: u8,
+    /// The `EI_OSABI` of the library.
+    pub os_abi: u8,
+    /// The `e_machine` of the library.
+    pub machine: u16,
+    /// Symbol versions the library requires from its dependencies.
+    pub required_versions: Vec<VersionRequirement>,
+    /// Everything that would prevent the library from being loaded into the running process.
+    pub incompatibilities: Vec<Incompatibility>,
+}
+
+impl CompatibilityReport {
+    /// Whether no incompatibilities have been found.
+    pub fn is_compatible(&self) -> bool {
+        self.incompatibilities.is_empty()
+    }
+}
+
+/// A symbol version a library requires from one of its dependencies.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct VersionRequirement {
+    /// The dependency expected to provide the version, e.g. `libc.so.6`.
+    pub file: String,
+    /// The version name, e.g. `GLIBC_2.34`.
+    pub version: String,
+    /// Whether a copy of `file` loaded into the running process provides the version.
+    ///
+    /// `None` if the dependency is not loaded into the running process and therefore could not
+    /// be checked.
+    pub satisfied: Option<bool>,
+}
+
+pub(super) fn check(path: &Path) -> Result<CompatibilityReport, Error> {
+    let mut elf = ElfFile::open(path)?;
+    let header = elf.header();
+    let (class, data, os_abi, machine) = (header.class, header.data, header.os_abi, header.machine);
+    let mut incompatibilities = header_incompatibilities(header);
+
+    let mut required_versions = Vec::new();
+    // Version requirements of an object built for a different machine are meaningless for the
+    // running process.
+    if incompatibilities.is_empty() {
+        let loaded = loaded_objects();
+        for need in elf.version_needs()? {
+            let file = String::from_utf8_lossy(&need.file).into_owned();
+            let provided = loaded
+                .iter()
+                .find(|path| path.file_name().map(|n| n.as_encoded_bytes()) == Some(&*need.file))
+                .and_then(|path| ElfFile::open(path).ok())
+                .and_then(|mut provider| provider.version_definitions().ok());
+            for version in need.versions {
+                let satisfied = provided.as_ref().map(|defs| defs.contains(&version));
+                let version = String::from_utf8_lossy(&version).into_owned();
+                if satisfied == Some(false) {
+                    incompatibilities.push(Incompatibility::MissingVersion {
+                        file: file.clone(),
+                        version: version.clone(),
+                    });
+                }
+                required_versions.push(VersionRequirement {
+                    file: file.clone(),
+                    version,
+                    satisfied,
+                });
+            }
+        }
+    }
+
+    Ok(CompatibilityReport {
+        class,
+        data,
+        os_abi,
+        machine,
+        required_versions,
+        incompatibilities,
+    })
+}
+
+/// Whether an object with this `header` can be loaded into the running process at all.
+pub(super) fn header_matches_process(header: &Header) -> bool {
+    header_incompatibilities(header).is_empty()
+}
+
+fn header_incompatibilities(header: &Header) -> Vec<Incompatibility> {
+    let mut incompatibilities = Vec::new();
+    let expected_class = if cfg!(target_pointer_width = "64") {
+        ELFCLASS64
+    } else {
+        ELFCLASS32
+    };
+    if header.class != expected_class {
+        incompatibilities.push(Incompatibility::Class {
+            found: header.class,
+            expected: expected_class,
+        });
+    }
+    let expected_data = if cfg!(target_endian = "little") {
+        ELFDATA2LSB
+    } else {
+        ELFDATA2MSB
+    };
+    if header.data != expected_data {
+        incompatibilities.push(Incompatibility::Endianness {
+            found: header.data,
+            expected: expected_data,
+        });
+    }
+    if let Some(expected) = EXPECTED_MACHINE {
+        if header.machine != expected {
+            incompatibilities.push(Incompatibility::Machine {
+                found: header.machine,
+                expected,
+            });
+        }
+    }
+    if !ACCEPTED_OS_ABIS.contains(&header.os_abi) {
+        incompatibilities.push(Incompatibility::OsAbi {
+            found: header.os_abi,
+        });
+    }
+    incompatibilities
+}
+
+/// Paths of all the objects currently loaded into the process.
+pub(super) fn loaded_objects() -> Vec<PathBuf> {
+    unsafe extern "C" fn callback(info: *mut DlPhdrInfo, _: usize, data: *mut c_void) -> c_int {
+        let paths = &mut *data.cast::<Vec<PathBuf>>();
+        let name = (*info).dlpi_name;
+        if !name.is_null() {
+            let name = CStr::from_ptr(name).to_bytes();
+            // The main program has an empty name; the vDSO has a name that is not a path.
+            if name.contains(&b'/') {
+                paths.push(PathBuf::from(std::ffi::OsStr::from_bytes(name)));
+            }
+        }
+        0
+    }
+
+    let mut paths = Vec::<PathBuf>::new();
+    unsafe {
+        dl_iterate_phdr(callback, (&raw mut paths).cast());
+    }
+    paths
+}
+
+/// The `e_machine` of objects that can be loaded into the running process, if known.
+const EXPECTED_MACHINE: Option<u16> = if cfg!(target_arch = "x86") {
+    Some(3)
+} else if cfg!(target_arch = "x86_64") {
+    Some(62)
+} else if cfg!(target_arch = "arm") {
+    Some(40)
+} else if cfg!(target_arch = "aarch64") {
+    Some(183)
+} else if cfg!(any(target_arch = "riscv32", target_arch = "riscv64")) {
+    Some(243)
+} else if cfg!(target_arch = "powerpc") {
+    Some(20)
+} else if cfg!(target_arch = "powerpc64") {
+    Some(21)
+} else if cfg!(any(target_arch = "mips", target_arch = "mips64")) {
+    Some(8)
+} else if cfg!(target_arch = "s390x") {
+    Some(22)
+} else if cfg!(target_arch = "sparc64") {
+    Some(43)
+} else if cfg!(target_arch = "loongarch64") {
+    Some(258)
+} else {
+    None
+};
+
+/// `ELFOSABI_NONE` and `ELFOSABI_GNU` (also known as `ELFOSABI_LINUX`).
+const ACCEPTED_OS_ABIS: &[u8] = &[0, 3];
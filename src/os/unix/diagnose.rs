@@ -0,0 +1,158 @@
+use super::compat::loaded_objects;
+use super::dynamic::{locate, resolve, search_path, Dependency, DynamicSection, SearchLocation};
+use super::ld_cache::LdSoCache;
+use crate::as_filename::AsFilename;
+use crate::Error;
+use alloc::borrow::ToOwned;
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+/// An explanation of why loading a library may fail, produced by [`diagnose_load_failure`].
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct LoadDiagnosis {
+    /// The library that has been diagnosed.
+    pub path: PathBuf,
+    /// Every dependency that has been found, in the order they were found.
+    pub found: Vec<Dependency>,
+    /// Every dependency that could not be found.
+    pub missing: Vec<MissingDependency>,
+}
+
+impl LoadDiagnosis {
+    /// Whether all of the dependencies have been found.
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty()
+    }
+}
+
+/// A dependency that could not be found.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct MissingDependency {
+    /// The name in the `DT_NEEDED` entry.
+    pub name: OsString,
+    /// The library with the `DT_NEEDED` entry.
+    pub needed_by: PathBuf,
+    /// Every location searched for the dependency, in order.
+    pub searched: Vec<SearchLocation>,
+}
+
+impl core::fmt::Display for LoadDiagnosis {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        writeln!(f, "{}:", self.path.display())?;
+        for dependency in &self.found {
+            if let Some(path) = &dependency.path {
+                writeln!(f, "\t{} => {}", dependency.name.display(), path.display())?;
+            }
+        }
+        for missing in &self.missing {
+            writeln!(
+                f,
+                "\t{} => not found (needed by {})",
+                missing.name.display(),
+                missing.needed_by.display()
+            )?;
+            for location in &missing.searched {
+                writeln!(
+                    f,
+                    "\t\tsearched {} ({})",
+                    location.path.display(),
+                    location.kind
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Find out which dependencies of a library cannot be found.
+///
+/// When `dlopen` fails because a transitive dependency of the library cannot be found, the error
+/// it reports is not particularly helpful. This function walks the tree of `DT_NEEDED` entries
+/// starting at `filename` and reports, similarly to `ldd`, which dependencies could not be found
+/// and every location that has been searched for them. See
+/// [`DynamicSection::resolve_needed`] for the search order.
+///
+/// Dependencies already loaded into the running process are considered found, just like the
+/// dynamic loader would. The files are only read; no initialisation routines are executed. A
+/// `filename` without a `/` is looked up with [`resolve_library_name`](super::resolve_library_name)
+/// first, failing with [`Error::LibraryNotFound`] if it is not found.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use ::libloading::os::unix::{Library, diagnose_load_failure};
+/// if let Err(error) = unsafe { Library::new("/path/to/awesome.module") } {
+///     eprintln!("{error}");
+///     if let Ok(diagnosis) = diagnose_load_failure("/path/to/awesome.module") {
+///         eprintln!("{diagnosis}");
+///     }
+/// }
+/// ```
+pub fn diagnose_load_failure(filename: impl AsFilename) -> Result<LoadDiagnosis, Error> {
+    filename.posix_filename(|posix_filename| unsafe {
+        // SAFE: `posix_filename` is a null-terminated string.
+        diagnose(&locate(super::char_ptr_to_path(posix_filename))?)
+    })
+}
+
+fn diagnose(path: &Path) -> Result<LoadDiagnosis, Error> {
+    // The dynamic loader matches `DT_NEEDED` entries against the `DT_SONAME` of the loaded
+    // objects, whatever the name of the file they have been loaded from.
+    let loaded: Vec<_> = loaded_objects()
+        .into_iter()
+        .map(|path| {
+            let soname = DynamicSection::read_path(&path)
+                .ok()
+                .and_then(|dynamic| dynamic.soname);
+            (path, soname)
+        })
+        .collect();
+    let cache = LdSoCache::system();
+    let mut diagnosis = LoadDiagnosis {
+        path: path.to_owned(),
+        found: Vec::new(),
+        missing: Vec::new(),
+    };
+    let mut seen = Vec::<OsString>::new();
+    let mut queue = VecDeque::from([DynamicSection::read_path(path)?]);
+    while let Some(object) = queue.pop_front() {
+        let search_path = search_path(&object, cache.as_ref());
+        for name in &object.needed {
+            if seen.contains(name) {
+                continue;
+            }
+            seen.push(name.clone());
+            let already_loaded = loaded.iter().find(|(path, soname)| match soname {
+                Some(soname) => soname == name,
+                None => path.file_name() == Some(name.as_os_str()),
+            });
+            if let Some((loaded, _)) = already_loaded {
+                diagnosis.found.push(Dependency {
+                    name: name.clone(),
+                    path: Some(loaded.clone()),
+                });
+            } else if let Some(found) = resolve(name, &search_path, cache.as_ref()) {
+                // A dependency that cannot be read is still found as far as the loader is
+                // concerned, it will fail to load for a different reason.
+                if let Ok(dependency) = DynamicSection::read_path(&found) {
+                    queue.push_back(dependency);
+                }
+                diagnosis.found.push(Dependency {
+                    name: name.clone(),
+                    path: Some(found),
+                });
+            } else {
+                diagnosis.missing.push(MissingDependency {
+                    name: name.clone(),
+                    needed_by: object.path.clone(),
+                    searched: search_path.clone(),
+                });
+            }
+        }
+    }
+    Ok(diagnosis)
+}
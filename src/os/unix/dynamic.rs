@@ -0,0 +1,479 @@
+use super::compat::header_matches_process;
+use super::elf::ElfFile;
+use super::ld_cache::{supported_hwcaps, LdSoCache};
+use crate::as_filename::AsFilename;
+use crate::Error;
+use alloc::borrow::ToOwned;
+use alloc::vec::Vec;
+use std::ffi::{OsStr, OsString};
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+
+const DT_NEEDED: u64 = 1;
+const DT_SONAME: u64 = 14;
+const DT_RPATH: u64 = 15;
+const DT_RUNPATH: u64 = 29;
+const DT_FLAGS: u64 = 30;
+const DT_FLAGS_1: u64 = 0x6fff_fffb;
+
+/// `DT_FLAGS`: the object may reference the `$ORIGIN` substitution string.
+pub const DF_ORIGIN: u64 = 0x1;
+/// `DT_FLAGS`: symbol resolution starts from the object itself.
+pub const DF_SYMBOLIC: u64 = 0x2;
+/// `DT_FLAGS`: relocations may modify a non-writable segment.
+pub const DF_TEXTREL: u64 = 0x4;
+/// `DT_FLAGS`: all relocations must be processed before control returns to the program.
+pub const DF_BIND_NOW: u64 = 0x8;
+/// `DT_FLAGS`: the object uses the static thread-local storage model.
+pub const DF_STATIC_TLS: u64 = 0x10;
+
+/// `DT_FLAGS_1`: equivalent to [`DF_BIND_NOW`].
+pub const DF_1_NOW: u64 = 0x1;
+/// `DT_FLAGS_1`: the object's symbols are made available globally, as if by `RTLD_GLOBAL`.
+pub const DF_1_GLOBAL: u64 = 0x2;
+/// `DT_FLAGS_1`: the object cannot be unloaded, as if loaded with `RTLD_NODELETE`.
+pub const DF_1_NODELETE: u64 = 0x8;
+/// `DT_FLAGS_1`: the object's initialisers run before those of other objects.
+pub const DF_1_INITFIRST: u64 = 0x20;
+/// `DT_FLAGS_1`: the object cannot be loaded with `dlopen`.
+pub const DF_1_NOOPEN: u64 = 0x40;
+/// `DT_FLAGS_1`: the object may reference the `$ORIGIN` substitution string.
+pub const DF_1_ORIGIN: u64 = 0x80;
+/// `DT_FLAGS_1`: the object's symbols interpose those of all objects loaded after it.
+pub const DF_1_INTERPOSE: u64 = 0x400;
+/// `DT_FLAGS_1`: the default library search path is ignored for the object's dependencies.
+pub const DF_1_NODEFLIB: u64 = 0x800;
+/// `DT_FLAGS_1`: the object is a position-independent executable.
+pub const DF_1_PIE: u64 = 0x0800_0000;
+
+/// The contents of a library file's dynamic section.
+///
+/// This is the information `readelf -d` would print: the name of the library, the libraries it
+/// depends on and where the dynamic loader will look for them.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use ::libloading::os::unix::{DynamicSection, DF_1_NODELETE};
+/// let dynamic = DynamicSection::read("/path/to/awesome.module").unwrap();
+/// println!("soname: {:?}", dynamic.soname);
+/// if dynamic.flags_1 & DF_1_NODELETE != 0 {
+///     println!("awesome.module will never be unloaded");
+/// }
+/// for dependency in dynamic.resolve_needed() {
+///     println!("{:?} => {:?}", dependency.name, dependency.path);
+/// }
+/// ```
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct DynamicSection {
+    /// The path the dynamic section has been read from.
+    pub path: PathBuf,
+    /// `DT_SONAME`: the name of the library.
+    pub soname: Option<OsString>,
+    /// `DT_NEEDED`: the libraries this library depends on, in order.
+    pub needed: Vec<OsString>,
+    /// `DT_RPATH`: directories to search for dependencies, split at `:`.
+    pub rpath: Vec<OsString>,
+    /// `DT_RUNPATH`: directories to search for dependencies, split at `:`.
+    pub runpath: Vec<OsString>,
+    /// `DT_FLAGS`: a combination of the `DF_*` flags.
+    pub flags: u64,
+    /// `DT_FLAGS_1`: a combination of the `DF_1_*` flags.
+    pub flags_1: u64,
+}
+
+/// A `DT_NEEDED` entry resolved to a file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Dependency {
+    /// The name in the `DT_NEEDED` entry.
+    pub name: OsString,
+    /// The file the dynamic loader would load, or `None` if no suitable file was found.
+    pub path: Option<PathBuf>,
+}
+
+impl DynamicSection {
+    /// Read the dynamic section of the library file at `filename`.
+    ///
+    /// The file is only read; no initialisation routines are executed. A `filename` without a `/`
+    /// is looked up with [`resolve_library_name`](super::resolve_library_name) first, failing
+    /// with [`Error::LibraryNotFound`] if it is not found.
+    pub fn read(filename: impl AsFilename) -> Result<DynamicSection, Error> {
+        filename.posix_filename(|posix_filename| unsafe {
+            // SAFE: `posix_filename` is a null-terminated string.
+            DynamicSection::read_path(&locate(super::char_ptr_to_path(posix_filename))?)
+        })
+    }
+
+    /// The dynamic section of the running executable, or an empty one if it cannot be read.
+    pub(super) fn executable() -> DynamicSection {
+        DynamicSection::read_path(Path::new(EXECUTABLE)).unwrap_or_else(|_| DynamicSection {
+            path: PathBuf::from(EXECUTABLE),
+            soname: None,
+            needed: Vec::new(),
+            rpath: Vec::new(),
+            runpath: Vec::new(),
+            flags: 0,
+            flags_1: 0,
+        })
+    }
+
+    pub(super) fn read_path(path: &Path) -> Result<DynamicSection, Error> {
+        fn split(entries: Vec<&[u8]>) -> Vec<OsString> {
+            entries
+                .into_iter()
+                .flat_map(|entry| entry.split(|&b| b == b':'))
+                .map(|dir| OsStr::from_bytes(dir).to_owned())
+                .collect()
+        }
+
+        let dynamic = ElfFile::open(path)?.dynamic()?;
+        Ok(DynamicSection {
+            path: path.to_owned(),
+            soname: dynamic
+                .strings(DT_SONAME)?
+                .first()
+                .map(|name| OsStr::from_bytes(name).to_owned()),
+            needed: dynamic
+                .strings(DT_NEEDED)?
+                .into_iter()
+                .map(|name| OsStr::from_bytes(name).to_owned())
+                .collect(),
+            rpath: split(dynamic.strings(DT_RPATH)?),
+            runpath: split(dynamic.strings(DT_RUNPATH)?),
+            flags: dynamic.value(DT_FLAGS).unwrap_or(0),
+            flags_1: dynamic.value(DT_FLAGS_1).unwrap_or(0),
+        })
+    }
+
+    /// Find the files the dynamic loader would load to satisfy the `DT_NEEDED` entries.
+    ///
+    /// Names containing a `/` are interpreted as paths. Other names are looked up the way the
+    /// GNU dynamic loader does it, in the following locations:
+    ///
+    /// 1. `DT_RPATH` of this library and then of the executable, unless the respective object has a
+    ///    `DT_RUNPATH`;
+    /// 2. `LD_LIBRARY_PATH`;
+    /// 3. `DT_RUNPATH` of this library;
+    /// 4. `/etc/ld.so.cache` and then the default directories, unless this library is flagged
+    ///    with [`DF_1_NODEFLIB`].
+    ///
+    /// `$ORIGIN`, `$LIB` and `$PLATFORM` in these are expanded, with `$ORIGIN` in
+    /// `LD_LIBRARY_PATH` referring to the directory of the executable. In each of the directories
+    /// the `glibc-hwcaps` subdirectories supported by the processor are searched first. Files built
+    /// for a different architecture are skipped, just like the dynamic loader does.
+    ///
+    /// This is an approximation of the search, which may differ from the dynamic loader in some
+    /// cases:
+    ///
+    /// * `$LIB` is taken to be the directory the C library has been loaded from, relative to `/`
+    ///   or `/usr`, such as `lib64` or `lib/x86_64-linux-gnu`, falling back to `lib64` or `lib`.
+    ///   The dynamic loader uses the value it has been configured with when it was built instead.
+    /// * The default directories are those of glibc and the Debian multiarch layout. The musl
+    ///   dynamic loader searches the directories listed in `/etc/ld-musl-<arch>.path` instead,
+    ///   and does not expand `$LIB` and `$PLATFORM`.
+    /// * Only the `DT_RPATH` of this library and of the executable are searched. The dynamic
+    ///   loader also searches the `DT_RPATH` of every object in the chain of dependencies that led
+    ///   to loading this library, unless that object has a `DT_RUNPATH`, so a dependency of a
+    ///   library loaded as a dependency itself may be reported as not found even though it would
+    ///   be loaded.
+    pub fn resolve_needed(&self) -> Vec<Dependency> {
+        let cache = LdSoCache::system();
+        let search_path = search_path(self, cache.as_ref());
+        self.needed
+            .iter()
+            .map(|name| Dependency {
+                name: name.clone(),
+                path: resolve(name, &search_path, cache.as_ref()),
+            })
+            .collect()
+    }
+}
+
+/// Where a location searched for a dependency comes from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SearchKind {
+    /// `DT_RPATH` of the library or the executable.
+    Rpath,
+    /// The `LD_LIBRARY_PATH` environment variable.
+    LdLibraryPath,
+    /// `DT_RUNPATH` of the library.
+    Runpath,
+    /// The cache maintained by `ldconfig`, see [`LdSoCache`].
+    Cache,
+    /// The default directories of the dynamic loader.
+    Default,
+}
+
+impl core::fmt::Display for SearchKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            SearchKind::Rpath => "RPATH",
+            SearchKind::LdLibraryPath => "LD_LIBRARY_PATH",
+            SearchKind::Runpath => "RUNPATH",
+            SearchKind::Cache => "ld.so.cache",
+            SearchKind::Default => "default",
+        })
+    }
+}
+
+/// A location searched for a dependency.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SearchLocation {
+    /// Where the location comes from.
+    pub kind: SearchKind,
+    /// The directory searched, or the cache file for [`SearchKind::Cache`].
+    pub path: PathBuf,
+}
+
+/// The locations searched for dependencies of `object`, in order.
+///
+/// `cache` is consulted in place of `/etc/ld.so.cache`.
+pub(super) fn search_path(
+    object: &DynamicSection,
+    cache: Option<&LdSoCache>,
+) -> Vec<SearchLocation> {
+    let mut dirs = Vec::new();
+    let mut push = |kind, owner: &Path, entries: &[OsString]| {
+        for entry in entries {
+            dirs.push(SearchLocation {
+                kind,
+                path: expand_tokens(entry, owner),
+            });
+        }
+    };
+    if object.runpath.is_empty() {
+        push(SearchKind::Rpath, &object.path, &object.rpath);
+        let executable = DynamicSection::executable();
+        if object.path != executable.path && executable.runpath.is_empty() {
+            let exe = std::env::current_exe().unwrap_or(executable.path);
+            push(SearchKind::Rpath, &exe, &executable.rpath);
+        }
+    }
+    if let Some(paths) = std::env::var_os("LD_LIBRARY_PATH") {
+        let entries: Vec<_> = paths
+            .as_bytes()
+            .split(|&b| b == b':' || b == b';')
+            .map(|dir| OsStr::from_bytes(dir).to_owned())
+            .collect();
+        // `$ORIGIN` in `LD_LIBRARY_PATH` refers to the executable, not the object being resolved.
+        let exe = std::env::current_exe().unwrap_or_else(|_| PathBuf::from(EXECUTABLE));
+        push(SearchKind::LdLibraryPath, &exe, &entries);
+    }
+    push(SearchKind::Runpath, &object.path, &object.runpath);
+    if object.flags_1 & DF_1_NODEFLIB == 0 {
+        if let Some(cache) = cache {
+            dirs.push(SearchLocation {
+                kind: SearchKind::Cache,
+                path: cache.path().to_owned(),
+            });
+        }
+        dirs.extend(default_dirs().into_iter().map(|path| SearchLocation {
+            kind: SearchKind::Default,
+            path,
+        }));
+    }
+    dirs
+}
+
+/// Find `name` in the `search_path` the way the dynamic loader does.
+///
+/// `cache` is the cache that [`SearchKind::Cache`] locations refer to.
+pub(super) fn resolve(
+    name: &OsStr,
+    search_path: &[SearchLocation],
+    cache: Option<&LdSoCache>,
+) -> Option<PathBuf> {
+    if name.as_bytes().contains(&b'/') {
+        let path = PathBuf::from(name);
+        return is_loadable(&path).then_some(path);
+    }
+    let hwcaps = supported_hwcaps();
+    search_path.iter().find_map(|location| match location.kind {
+        SearchKind::Cache => cache.and_then(|cache| cache.lookup_loadable(name)),
+        _ => hwcaps
+            .iter()
+            .map(|hwcaps| location.path.join("glibc-hwcaps").join(hwcaps).join(name))
+            .chain([location.path.join(name)])
+            .find(|candidate| is_loadable(candidate)),
+    })
+}
+
+/// The file `path` refers to: `path` itself if it contains a `/`, or otherwise the file the
+/// dynamic loader would find for it, failing with [`Error::LibraryNotFound`] if there is none.
+pub(super) fn locate(path: &Path) -> Result<PathBuf, Error> {
+    if path.as_os_str().as_bytes().contains(&b'/') {
+        return Ok(path.to_owned());
+    }
+    resolve_for_executable(path.as_os_str(), LdSoCache::system().as_ref()).ok_or_else(|| {
+        Error::LibraryNotFound {
+            name: path.as_os_str().to_owned(),
+        }
+    })
+}
+
+/// Find `name` the way `dlopen` called from the running executable does.
+pub(super) fn resolve_for_executable(name: &OsStr, cache: Option<&LdSoCache>) -> Option<PathBuf> {
+    let executable = DynamicSection::executable();
+    resolve(name, &search_path(&executable, cache), cache)
+}
+
+/// Whether the file at `path` is an object the running process could load.
+pub(super) fn is_loadable(path: &Path) -> bool {
+    ElfFile::open(path).is_ok_and(|elf| header_matches_process(elf.header()))
+}
+
+/// Expand the dynamic string tokens in a search path `entry` of the object at `owner`.
+///
+/// An empty entry refers to the current working directory.
+fn expand_tokens(entry: &OsStr, owner: &Path) -> PathBuf {
+    let origin = owner
+        .canonicalize()
+        .ok()
+        .and_then(|path| path.parent().map(Path::to_owned))
+        .or_else(|| owner.parent().map(Path::to_owned))
+        .unwrap_or_default();
+    let lib = lib_token();
+    let mut expanded = Vec::new();
+    let mut rest = entry.as_bytes();
+    while let Some(position) = rest.iter().position(|&b| b == b'$') {
+        expanded.extend_from_slice(&rest[..position]);
+        rest = &rest[position + 1..];
+        let substitutions: [(&[u8], &[u8]); 3] = [
+            (b"ORIGIN", origin.as_os_str().as_bytes()),
+            (b"LIB", lib.as_os_str().as_bytes()),
+            (b"PLATFORM", std::env::consts::ARCH.as_bytes()),
+        ];
+        let token = substitutions.iter().find_map(|(token, value)| {
+            if rest.starts_with(token) {
+                Some((token.len(), value))
+            } else if rest.first() == Some(&b'{') && rest[1..].starts_with(token) {
+                (rest.get(token.len() + 1) == Some(&b'}')).then_some((token.len() + 2, value))
+            } else {
+                None
+            }
+        });
+        match token {
+            Some((length, value)) => {
+                expanded.extend_from_slice(value);
+                rest = &rest[length..];
+            }
+            None => expanded.push(b'$'),
+        }
+    }
+    expanded.extend_from_slice(rest);
+    if expanded.is_empty() {
+        PathBuf::from(".")
+    } else {
+        PathBuf::from(OsStr::from_bytes(&expanded))
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(any(target_os = "android", target_env = "musl"))] {
+        const MULTIARCH: Option<&str> = None;
+    } else if #[cfg(all(target_arch = "x86_64", target_pointer_width = "64"))] {
+        const MULTIARCH: Option<&str> = Some("x86_64-linux-gnu");
+    } else if #[cfg(target_arch = "x86_64")] {
+        const MULTIARCH: Option<&str> = Some("x86_64-linux-gnux32");
+    } else if #[cfg(target_arch = "x86")] {
+        const MULTIARCH: Option<&str> = Some("i386-linux-gnu");
+    } else if #[cfg(target_arch = "aarch64")] {
+        const MULTIARCH: Option<&str> = Some("aarch64-linux-gnu");
+    } else if #[cfg(all(target_arch = "arm", target_abi = "eabihf"))] {
+        const MULTIARCH: Option<&str> = Some("arm-linux-gnueabihf");
+    } else if #[cfg(target_arch = "arm")] {
+        const MULTIARCH: Option<&str> = Some("arm-linux-gnueabi");
+    } else if #[cfg(target_arch = "riscv64")] {
+        const MULTIARCH: Option<&str> = Some("riscv64-linux-gnu");
+    } else if #[cfg(all(target_arch = "powerpc64", target_endian = "little"))] {
+        const MULTIARCH: Option<&str> = Some("powerpc64le-linux-gnu");
+    } else if #[cfg(target_arch = "powerpc64")] {
+        const MULTIARCH: Option<&str> = Some("powerpc64-linux-gnu");
+    } else if #[cfg(target_arch = "powerpc")] {
+        const MULTIARCH: Option<&str> = Some("powerpc-linux-gnu");
+    } else if #[cfg(target_arch = "s390x")] {
+        const MULTIARCH: Option<&str> = Some("s390x-linux-gnu");
+    } else if #[cfg(all(target_arch = "mips64", target_endian = "little"))] {
+        const MULTIARCH: Option<&str> = Some("mips64el-linux-gnuabi64");
+    } else if #[cfg(target_arch = "mips64")] {
+        const MULTIARCH: Option<&str> = Some("mips64-linux-gnuabi64");
+    } else if #[cfg(all(target_arch = "mips", target_endian = "little"))] {
+        const MULTIARCH: Option<&str> = Some("mipsel-linux-gnu");
+    } else if #[cfg(target_arch = "mips")] {
+        const MULTIARCH: Option<&str> = Some("mips-linux-gnu");
+    } else if #[cfg(target_arch = "loongarch64")] {
+        const MULTIARCH: Option<&str> = Some("loongarch64-linux-gnu");
+    } else if #[cfg(target_arch = "sparc64")] {
+        const MULTIARCH: Option<&str> = Some("sparc64-linux-gnu");
+    } else {
+        const MULTIARCH: Option<&str> = None;
+    }
+}
+
+/// The path of the running executable.
+const EXECUTABLE: &str = "/proc/self/exe";
+
+/// Directories searched after all the others.
+fn default_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(multiarch) = MULTIARCH {
+        dirs.push(Path::new("/lib").join(multiarch));
+        dirs.push(Path::new("/usr/lib").join(multiarch));
+    }
+    dirs.extend(DEFAULT_DIRS.iter().map(PathBuf::from));
+    dirs
+}
+
+/// The expansion of `$LIB`: the directory the C library has been loaded from, relative to `/` or
+/// `/usr`, falling back to [`LIB`].
+fn lib_token() -> PathBuf {
+    let mut info = core::mem::MaybeUninit::<super::DlInfo>::uninit();
+    // `dlopen` is defined by the C library, or by `libdl` next to it with older glibc.
+    let address = super::dlopen as *const () as *mut core::ffi::c_void;
+    if unsafe { super::dladdr(address, info.as_mut_ptr()) } != 0 {
+        let info = unsafe { info.assume_init() };
+        if !info.dli_fname.is_null() {
+            let filename = unsafe { core::ffi::CStr::from_ptr(info.dli_fname) };
+            let directory = Path::new(OsStr::from_bytes(filename.to_bytes())).parent();
+            let relative = directory.and_then(|directory| {
+                directory
+                    .strip_prefix("/usr")
+                    .or_else(|_| directory.strip_prefix("/"))
+                    .ok()
+            });
+            if let Some(relative) =
+                relative.filter(|relative| relative.as_os_str().as_bytes().starts_with(b"lib"))
+            {
+                return relative.to_owned();
+            }
+        }
+    }
+    PathBuf::from(LIB)
+}
+
+/// The expansion of `$LIB` if the directory of the C library cannot be determined.
+const LIB: &str = if cfg!(target_pointer_width = "64") {
+    "lib64"
+} else {
+    "lib"
+};
+
+/// Directories searched after all the others, following the multiarch ones.
+#[cfg(target_os = "android")]
+const DEFAULT_DIRS: &[&str] = if cfg!(target_pointer_width = "64") {
+    &["/system/lib64", "/vendor/lib64"]
+} else {
+    &["/system/lib", "/vendor/lib"]
+};
+
+/// Directories searched after all the others, following the multiarch ones.
+#[cfg(not(target_os = "android"))]
+const DEFAULT_DIRS: &[&str] = if cfg!(target_pointer_width = "64") {
+    &["/lib64", "/usr/lib64", "/lib", "/usr/lib"]
+} else {
+    &["/lib", "/usr/lib"]
+};
@@ -0,0 +1,238 @@
+//! Sizes of symbols, as recorded in the dynamic symbol tables of the loaded objects.
+
+#[cfg(any(feature = "std", not(all(target_os = "linux", target_env = "gnu"))))]
+use core::ffi::c_char;
+use core::ffi::{c_int, c_void};
+
+/// The size of the symbol starting at `address`, as recorded in the dynamic symbol table of the
+/// object containing it.
+///
+/// `None` if no symbol starts at `address`.
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+pub(crate) fn symbol_size(address: *const c_void) -> Option<usize> {
+    const RTLD_DL_SYMENT: c_int = 1;
+    unsafe {
+        let mut info = core::mem::MaybeUninit::<super::DlInfo>::uninit();
+        let mut symbol: *const ElfSym = core::ptr::null();
+        if dladdr1(
+            address,
+            info.as_mut_ptr(),
+            (&raw mut symbol).cast(),
+            RTLD_DL_SYMENT,
+        ) == 0
+            || symbol.is_null()
+            || info.assume_init().dli_saddr.cast_const() != address
+        {
+            return None;
+        }
+        usize::try_from((*symbol).st_size).ok()
+    }
+}
+
+/// The size of the symbol starting at `address`, as recorded in the dynamic symbol table of the
+/// object containing it.
+///
+/// `None` if no symbol starts at `address`.
+///
+/// Without `dladdr1`, the object containing `address` is found with `dl_iterate_phdr`, and its
+/// dynamic symbol table is searched in memory.
+#[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+pub(crate) fn symbol_size(address: *const c_void) -> Option<usize> {
+    struct Search {
+        address: usize,
+        size: Option<usize>,
+    }
+
+    unsafe extern "C" fn callback(info: *mut DlPhdrInfo, _: usize, data: *mut c_void) -> c_int {
+        let search = &mut *data.cast::<Search>();
+        let info = &*info;
+        let headers = core::slice::from_raw_parts(info.dlpi_phdr, usize::from(info.dlpi_phnum));
+        let contains = headers.iter().any(|header| {
+            let start = info.dlpi_addr.wrapping_add(header.p_vaddr as usize);
+            header.p_type == PT_LOAD
+                && (start..start.wrapping_add(header.p_memsz as usize)).contains(&search.address)
+        });
+        if !contains {
+            return 0;
+        }
+        if let Some(dynamic) = headers.iter().find(|header| header.p_type == PT_DYNAMIC) {
+            let dynamic = info.dlpi_addr.wrapping_add(dynamic.p_vaddr as usize) as *const ElfDyn;
+            search.size = find_in_object(info.dlpi_addr, dynamic, search.address);
+        }
+        // Stop iterating, the object containing the address has been found.
+        1
+    }
+
+    let mut search = Search {
+        address: address as usize,
+        size: None,
+    };
+    unsafe {
+        dl_iterate_phdr(callback, (&raw mut search).cast());
+    }
+    search.size
+}
+
+/// Search the dynamic symbol table of the object loaded at `base` with the dynamic section
+/// `dynamic` for a symbol starting at `address`.
+#[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+unsafe fn find_in_object(base: usize, dynamic: *const ElfDyn, address: usize) -> Option<usize> {
+    const DT_NULL: isize = 0;
+    const DT_HASH: isize = 4;
+    const DT_SYMTAB: isize = 6;
+    const DT_GNU_HASH: isize = 0x6fff_fef5;
+    const SHN_UNDEF: u16 = 0;
+
+    let mut symbols = None;
+    let mut hash = None;
+    let mut gnu_hash = None;
+    let mut entry = dynamic;
+    while (*entry).d_tag != DT_NULL {
+        // glibc relocates the addresses in the dynamic section in place, but it provides
+        // `dladdr1` instead. The loaders of the other C libraries on Linux and Android (musl,
+        // bionic and uClibc) leave them relative to the base address.
+        let value = base.wrapping_add((*entry).d_val);
+        match (*entry).d_tag {
+            DT_SYMTAB => symbols = Some(value as *const ElfSym),
+            DT_HASH => hash = Some(value as *const u32),
+            DT_GNU_HASH => gnu_hash = Some(value as *const u32),
+            _ => {}
+        }
+        entry = entry.add(1);
+    }
+    let symbols = symbols?;
+    let count = match (hash, gnu_hash) {
+        // The number of entries in the chain is the number of symbols.
+        (Some(hash), _) => *hash.add(1) as usize,
+        (None, Some(gnu_hash)) => gnu_hash_symbol_count(gnu_hash),
+        (None, None) => return None,
+    };
+    core::slice::from_raw_parts(symbols, count)
+        .iter()
+        .find(|symbol| {
+            symbol.st_shndx != SHN_UNDEF
+                && symbol.st_value != 0
+                && base.wrapping_add(symbol.st_value as usize) == address
+        })
+        .and_then(|symbol| usize::try_from(symbol.st_size).ok())
+}
+
+/// The number of symbols in a dynamic symbol table with the `DT_GNU_HASH` table at `table`.
+///
+/// The table does not record the number, but the last symbol is the last one in the longest
+/// chain, which ends with an entry with the lowest bit set.
+#[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+unsafe fn gnu_hash_symbol_count(table: *const u32) -> usize {
+    let bucket_count = *table as usize;
+    let symbol_offset = *table.add(1) as usize;
+    let bloom_size = *table.add(2) as usize;
+    let buckets = table.add(4).cast::<usize>().add(bloom_size).cast::<u32>();
+    let chains = buckets.add(bucket_count);
+    let last = core::slice::from_raw_parts(buckets, bucket_count)
+        .iter()
+        .max()
+        .map_or(0, |&bucket| bucket as usize);
+    if last < symbol_offset {
+        return symbol_offset;
+    }
+    let mut index = last;
+    while *chains.add(index - symbol_offset) & 1 == 0 {
+        index += 1;
+    }
+    index + 1
+}
+
+/// `ElfW(Sym)`, an entry of the dynamic symbol table.
+#[cfg(target_pointer_width = "64")]
+#[repr(C)]
+struct ElfSym {
+    st_name: u32,
+    st_info: u8,
+    st_other: u8,
+    st_shndx: u16,
+    st_value: u64,
+    st_size: u64,
+}
+
+/// `ElfW(Sym)`, an entry of the dynamic symbol table.
+#[cfg(target_pointer_width = "32")]
+#[repr(C)]
+struct ElfSym {
+    st_name: u32,
+    st_value: u32,
+    st_size: u32,
+    st_info: u8,
+    st_other: u8,
+    st_shndx: u16,
+}
+
+/// `ElfW(Dyn)`, an entry of the dynamic section.
+#[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+#[repr(C)]
+struct ElfDyn {
+    d_tag: isize,
+    d_val: usize,
+}
+
+#[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+const PT_LOAD: u32 = 1;
+#[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+const PT_DYNAMIC: u32 = 2;
+
+/// `ElfW(Phdr)`, a program header.
+#[cfg(any(feature = "std", not(all(target_os = "linux", target_env = "gnu"))))]
+#[cfg(target_pointer_width = "64")]
+#[repr(C)]
+pub(super) struct ElfPhdr {
+    p_type: u32,
+    p_flags: u32,
+    p_offset: u64,
+    p_vaddr: u64,
+    p_paddr: u64,
+    p_filesz: u64,
+    p_memsz: u64,
+    p_align: u64,
+}
+
+/// `ElfW(Phdr)`, a program header.
+#[cfg(any(feature = "std", not(all(target_os = "linux", target_env = "gnu"))))]
+#[cfg(target_pointer_width = "32")]
+#[repr(C)]
+pub(super) struct ElfPhdr {
+    p_type: u32,
+    p_offset: u32,
+    p_vaddr: u32,
+    p_paddr: u32,
+    p_filesz: u32,
+    p_memsz: u32,
+    p_flags: u32,
+    p_align: u32,
+}
+
+/// `struct dl_phdr_info`, describing a loaded object.
+///
+/// Shared with `compat`, as declaring `dl_iterate_phdr` with different types is an error.
+#[cfg(any(feature = "std", not(all(target_os = "linux", target_env = "gnu"))))]
+#[repr(C)]
+pub(super) struct DlPhdrInfo {
+    pub(super) dlpi_addr: usize,
+    pub(super) dlpi_name: *const c_char,
+    pub(super) dlpi_phdr: *const ElfPhdr,
+    pub(super) dlpi_phnum: u16,
+    // Further fields are not used and are not declared.
+}
+
+extern "C" {
+    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    fn dladdr1(
+        addr: *const c_void,
+        info: *mut super::DlInfo,
+        extra_info: *mut *mut c_void,
+        flags: c_int,
+    ) -> c_int;
+    #[cfg(any(feature = "std", not(all(target_os = "linux", target_env = "gnu"))))]
+    pub(super) fn dl_iterate_phdr(
+        callback: unsafe extern "C" fn(*mut DlPhdrInfo, usize, *mut c_void) -> c_int,
+        data: *mut c_void,
+    ) -> c_int;
+}
@@ -0,0 +1,405 @@
+//! A minimal reader for the parts of ELF object files that are of interest to `libloading`.
+//!
+//! Only the data necessary to answer questions about a library before it is loaded is read from
+//! the file: the file header, the section header table and the contents of individual sections
+//! as they are requested. Nothing is ever mapped or executed.
+use crate::Error;
+use alloc::vec::Vec;
+use std::fs::File;
+use std::os::unix::fs::FileExt;
+use std::path::Path;
+
+pub(crate) const ELFCLASS32: u8 = 1;
+pub(crate) const ELFCLASS64: u8 = 2;
+pub(crate) const ELFDATA2LSB: u8 = 1;
+pub(crate) const ELFDATA2MSB: u8 = 2;
+
+const SHT_DYNAMIC: u32 = 6;
+const SHT_GNU_VERDEF: u32 = 0x6fff_fffd;
+const SHT_GNU_VERNEED: u32 = 0x6fff_fffe;
+
+const VER_FLG_BASE: u16 = 0x1;
+
+/// The interesting fields of the ELF file header.
+pub(crate) struct Header {
+    pub(crate) class: u8,
+    pub(crate) data: u8,
+    pub(crate) os_abi: u8,
+    pub(crate) machine: u16,
+    shoff: u64,
+    shentsize: u16,
+    shnum: u64,
+}
+
+/// The interesting fields of a section header.
+#[derive(Clone)]
+pub(crate) struct Section {
+    pub(crate) kind: u32,
+    offset: u64,
+    size: u64,
+    link: u32,
+    info: u32,
+}
+
+/// A version requirement table entry: versions required from a specific dependency.
+pub(crate) struct VersionNeed {
+    pub(crate) file: Vec<u8>,
+    pub(crate) versions: Vec<Vec<u8>>,
+}
+
+/// The entries of the dynamic section along with the string table they refer to.
+pub(crate) struct Dynamic {
+    entries: Vec<(u64, u64)>,
+    strings: Vec<u8>,
+    little: bool,
+}
+
+impl Dynamic {
+    /// The value of the first entry with the `tag`.
+    pub(crate) fn value(&self, tag: u64) -> Option<u64> {
+        self.entries
+            .iter()
+            .find(|(t, _)| *t == tag)
+            .map(|(_, v)| *v)
+    }
+
+    /// The strings referred to by all the entries with the `tag`, in order.
+    pub(crate) fn strings(&self, tag: u64) -> Result<Vec<&[u8]>, Error> {
+        let strings = Bytes {
+            data: &self.strings,
+            little: self.little,
+        };
+        self.entries
+            .iter()
+            .filter(|(t, _)| *t == tag)
+            .map(|(_, v)| strings.string(*v))
+            .collect()
+    }
+}
+
+/// Endianness-aware accessors for a buffer read out of an ELF file.
+#[derive(Clone, Copy)]
+struct Bytes<'a> {
+    data: &'a [u8],
+    little: bool,
+}
+
+impl<'a> Bytes<'a> {
+    fn array<const N: usize>(&self, offset: u64) -> Result<[u8; N], Error> {
+        usize::try_from(offset)
+            .ok()
+            .and_then(|o| self.data.get(o..o.checked_add(N)?))
+            .and_then(|s| s.try_into().ok())
+            .ok_or(Error::MalformedObject)
+    }
+
+    fn u16(&self, offset: u64) -> Result<u16, Error> {
+        let bytes = self.array(offset)?;
+        Ok(if self.little {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    }
+
+    fn u32(&self, offset: u64) -> Result<u32, Error> {
+        let bytes = self.array(offset)?;
+        Ok(if self.little {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn u64(&self, offset: u64) -> Result<u64, Error> {
+        let bytes = self.array(offset)?;
+        Ok(if self.little {
+            u64::from_le_bytes(bytes)
+        } else {
+            u64::from_be_bytes(bytes)
+        })
+    }
+
+    /// Read an address-sized word, which is 4 bytes wide in 32-bit objects.
+    fn word(&self, class: u8, offset: u64) -> Result<u64, Error> {
+        if class == ELFCLASS64 {
+            self.u64(offset)
+        } else {
+            self.u32(offset).map(u64::from)
+        }
+    }
+
+    /// Read a null-terminated string out of a string table.
+    fn string(&self, offset: u64) -> Result<&'a [u8], Error> {
+        let rest = usize::try_from(offset)
+            .ok()
+            .and_then(|o| self.data.get(o..))
+            .ok_or(Error::MalformedObject)?;
+        let end = rest
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or(Error::MalformedObject)?;
+        Ok(&rest[..end])
+    }
+}
+
+/// An ELF file opened for inspection.
+pub(crate) struct ElfFile {
+    file: File,
+    length: u64,
+    header: Header,
+    sections: Option<Vec<Section>>,
+}
+
+impl ElfFile {
+    /// Open the file at `path` and read its file header.
+    ///
+    /// The section headers are only read once they are needed.
+    pub(crate) fn open(path: &Path) -> Result<ElfFile, Error> {
+        let file = File::open(path).map_err(|source| Error::Io {
+            source: source.into(),
+        })?;
+        let length = file
+            .metadata()
+            .map_err(|source| Error::Io {
+                source: source.into(),
+            })?
+            .len();
+        let mut ident = [0; 64];
+        let ident_length = usize::try_from(length)
+            .unwrap_or(usize::MAX)
+            .min(ident.len());
+        file.read_exact_at(&mut ident[..ident_length], 0)
+            .map_err(|source| Error::Io {
+                source: source.into(),
+            })?;
+        if ident[..4] != *b"\x7fELF" {
+            return Err(Error::MalformedObject);
+        }
+        let (class, data) = (ident[4], ident[5]);
+        let little = match data {
+            ELFDATA2LSB => true,
+            ELFDATA2MSB => false,
+            _ => return Err(Error::MalformedObject),
+        };
+        let bytes = Bytes {
+            data: &ident[..ident_length],
+            little,
+        };
+        let (shoff, shentsize, shnum) = match class {
+            ELFCLASS32 => (bytes.u32(32)?.into(), bytes.u16(46)?, bytes.u16(48)?),
+            ELFCLASS64 => (bytes.u64(40)?, bytes.u16(58)?, bytes.u16(60)?),
+            _ => return Err(Error::MalformedObject),
+        };
+        Ok(ElfFile {
+            file,
+            length,
+            header: Header {
+                class,
+                data,
+                os_abi: ident[7],
+                machine: bytes.u16(18)?,
+                shoff,
+                shentsize,
+                shnum: shnum.into(),
+            },
+            sections: None,
+        })
+    }
+
+    pub(crate) fn header(&self) -> &Header {
+        &self.header
+    }
+
+    fn bytes<'a>(&self, data: &'a [u8]) -> Bytes<'a> {
+        Bytes {
+            data,
+            little: self.header.data == ELFDATA2LSB,
+        }
+    }
+
+    /// Read `size` bytes at `offset`, refusing to read past the end of the file.
+    fn read(&self, offset: u64, size: u64) -> Result<Vec<u8>, Error> {
+        match offset.checked_add(size) {
+            Some(end) if end <= self.length => {}
+            _ => return Err(Error::MalformedObject),
+        }
+        let mut buffer = alloc::vec![0; usize::try_from(size).map_err(|_| Error::MalformedObject)?];
+        self.file
+            .read_exact_at(&mut buffer, offset)
+            .map_err(|source| Error::Io {
+                source: source.into(),
+            })?;
+        Ok(buffer)
+    }
+
+    fn sections(&mut self) -> Result<&[Section], Error> {
+        if self.sections.is_none() {
+            self.sections = Some(self.read_sections()?);
+        }
+        Ok(self.sections.as_deref().unwrap_or_default())
+    }
+
+    fn read_sections(&self) -> Result<Vec<Section>, Error> {
+        let Header {
+            class,
+            shoff,
+            shentsize,
+            mut shnum,
+            ..
+        } = self.header;
+        if shoff == 0 {
+            // Section headers are optional for loadable objects. There is nothing to read.
+            return Ok(Vec::new());
+        }
+        let entry_size = if class == ELFCLASS64 { 64 } else { 40 };
+        if u64::from(shentsize) < entry_size {
+            return Err(Error::MalformedObject);
+        }
+        if shnum == 0 {
+            // With many sections the real count is stored in the `sh_size` of the first entry.
+            let first = self.read(shoff, entry_size)?;
+            shnum = self
+                .bytes(&first)
+                .word(class, if class == ELFCLASS64 { 32 } else { 20 })?;
+        }
+        let table_size = shnum
+            .checked_mul(shentsize.into())
+            .ok_or(Error::MalformedObject)?;
+        let table = self.read(shoff, table_size)?;
+        let bytes = self.bytes(&table);
+        let mut sections = Vec::new();
+        for index in 0..shnum {
+            let base = index * u64::from(shentsize);
+            sections.push(if class == ELFCLASS64 {
+                Section {
+                    kind: bytes.u32(base + 4)?,
+                    offset: bytes.u64(base + 24)?,
+                    size: bytes.u64(base + 32)?,
+                    link: bytes.u32(base + 40)?,
+                    info: bytes.u32(base + 44)?,
+                }
+            } else {
+                Section {
+                    kind: bytes.u32(base + 4)?,
+                    offset: bytes.u32(base + 16)?.into(),
+                    size: bytes.u32(base + 20)?.into(),
+                    link: bytes.u32(base + 24)?,
+                    info: bytes.u32(base + 28)?,
+                }
+            });
+        }
+        Ok(sections)
+    }
+
+    fn section_of_kind(&mut self, kind: u32) -> Result<Option<Section>, Error> {
+        Ok(self.sections()?.iter().find(|s| s.kind == kind).cloned())
+    }
+
+    fn section_data(&self, section: &Section) -> Result<Vec<u8>, Error> {
+        self.read(section.offset, section.size)
+    }
+
+    /// Read the string table a section refers to via its `sh_link` field.
+    fn linked_strings(&mut self, section: &Section) -> Result<Vec<u8>, Error> {
+        let strtab = usize::try_from(section.link)
+            .ok()
+            .and_then(|i| self.sections().ok()?.get(i).cloned())
+            .ok_or(Error::MalformedObject)?;
+        self.section_data(&strtab)
+    }
+
+    /// Read the `.dynamic` section.
+    ///
+    /// Objects without a dynamic section (or without section headers) have no entries.
+    pub(crate) fn dynamic(&mut self) -> Result<Dynamic, Error> {
+        let little = self.header.data == ELFDATA2LSB;
+        let Some(section) = self.section_of_kind(SHT_DYNAMIC)? else {
+            return Ok(Dynamic {
+                entries: Vec::new(),
+                strings: Vec::new(),
+                little,
+            });
+        };
+        let data = self.section_data(&section)?;
+        let strings = self.linked_strings(&section)?;
+        let bytes = self.bytes(&data);
+        let class = self.header.class;
+        let entry_size = if class == ELFCLASS64 { 16 } else { 8 };
+        let mut entries = Vec::new();
+        let mut offset = 0;
+        while offset + entry_size <= section.size {
+            // Elf_Dyn: d_tag, d_val.
+            let tag = bytes.word(class, offset)?;
+            if tag == 0 {
+                break;
+            }
+            entries.push((tag, bytes.word(class, offset + entry_size / 2)?));
+            offset += entry_size;
+        }
+        Ok(Dynamic {
+            entries,
+            strings,
+            little,
+        })
+    }
+
+    /// Read the `.gnu.version_r` section: symbol versions this object requires.
+    pub(crate) fn version_needs(&mut self) -> Result<Vec<VersionNeed>, Error> {
+        let Some(section) = self.section_of_kind(SHT_GNU_VERNEED)? else {
+            return Ok(Vec::new());
+        };
+        let data = self.section_data(&section)?;
+        let strings = self.linked_strings(&section)?;
+        let (bytes, strings) = (self.bytes(&data), self.bytes(&strings));
+        let mut needs = Vec::new();
+        let mut offset = 0u64;
+        for _ in 0..section.info {
+            // Elf_Verneed: vn_version, vn_cnt, vn_file, vn_aux, vn_next.
+            let count = bytes.u16(offset + 2)?;
+            let file = strings.string(bytes.u32(offset + 4)?.into())?.to_vec();
+            let mut versions = Vec::new();
+            let mut aux = offset + u64::from(bytes.u32(offset + 8)?);
+            for _ in 0..count {
+                // Elf_Vernaux: vna_hash, vna_flags, vna_other, vna_name, vna_next.
+                versions.push(strings.string(bytes.u32(aux + 8)?.into())?.to_vec());
+                aux += u64::from(bytes.u32(aux + 12)?);
+            }
+            needs.push(VersionNeed { file, versions });
+            match bytes.u32(offset + 12)? {
+                0 => break,
+                next => offset += u64::from(next),
+            }
+        }
+        Ok(needs)
+    }
+
+    /// Read the `.gnu.version_d` section: symbol versions this object provides.
+    ///
+    /// The base definition, which names the object itself, is not included.
+    pub(crate) fn version_definitions(&mut self) -> Result<Vec<Vec<u8>>, Error> {
+        let Some(section) = self.section_of_kind(SHT_GNU_VERDEF)? else {
+            return Ok(Vec::new());
+        };
+        let data = self.section_data(&section)?;
+        let strings = self.linked_strings(&section)?;
+        let (bytes, strings) = (self.bytes(&data), self.bytes(&strings));
+        let mut definitions = Vec::new();
+        let mut offset = 0u64;
+        for _ in 0..section.info {
+            // Elf_Verdef: vd_version, vd_flags, vd_ndx, vd_cnt, vd_hash, vd_aux, vd_next.
+            let flags = bytes.u16(offset + 2)?;
+            if flags & VER_FLG_BASE == 0 {
+                // Elf_Verdaux: vda_name, vda_next. Only the first entry names this definition,
+                // the rest name its predecessors.
+                let aux = offset + u64::from(bytes.u32(offset + 12)?);
+                definitions.push(strings.string(bytes.u32(aux)?.into())?.to_vec());
+            }
+            match bytes.u32(offset + 16)? {
+                0 => break,
+                next => offset += u64::from(next),
+            }
+        }
+        Ok(definitions)
+    }
+}
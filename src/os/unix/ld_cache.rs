@@ -0,0 +1,416 @@
+use super::dynamic::{is_loadable, resolve_for_executable};
+use crate::as_filename::AsFilename;
+use crate::Error;
+use alloc::borrow::ToOwned;
+use alloc::vec::Vec;
+use std::ffi::{OsStr, OsString};
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+
+/// Where glibc keeps its cache.
+const SYSTEM_CACHE: &str = "/etc/ld.so.cache";
+
+/// Magic of the format used by glibc before 2.32 (and libc5).
+const OLD_MAGIC: &[u8] = b"ld.so-1.7.0";
+/// Size of the header of the old format: the magic padded to 12 bytes and the entry count.
+const OLD_HEADER_SIZE: usize = 16;
+/// Size of an entry in the old format.
+const OLD_ENTRY_SIZE: usize = 12;
+
+/// Magic and version of the format used since glibc 2.32.
+const NEW_MAGIC: &[u8] = b"glibc-ld.so.cache1.1";
+/// Size of the header of the new format.
+const NEW_HEADER_SIZE: usize = 48;
+/// Size of an entry in the new format.
+const NEW_ENTRY_SIZE: usize = 24;
+/// Alignment of the new format when it follows a cache in the old format.
+const NEW_ALIGNMENT: usize = 8;
+
+/// The `flags` byte of the new format header, describing its byte order.
+const ENDIAN_MASK: u8 = 3;
+const ENDIAN_BIG: u8 = 1;
+const ENDIAN_LITTLE: u8 = 2;
+
+/// `hwcap` of an entry that lives in a `glibc-hwcaps` subdirectory. The lower 32 bits are the
+/// index of the subdirectory name in the hwcaps extension.
+const HWCAP_EXTENSION: u64 = 1 << 62;
+const EXTENSION_MAGIC: u32 = 0xEAA4_2174;
+const EXTENSION_TAG_GLIBC_HWCAPS: u32 = 1;
+
+/// `FLAG_ELF`: an ELF library of no particular ABI.
+const FLAG_ELF: i32 = 1;
+
+cfg_if::cfg_if! {
+    // `_DL_CACHE_DEFAULT_ID`: `FLAG_ELF_LIBC6` combined with the architecture specific flag.
+    if #[cfg(all(target_arch = "x86_64", target_pointer_width = "64"))] {
+        const DEFAULT_ID: i32 = 0x0303;
+        const ACCEPTS_FLAG_ELF: bool = false;
+    } else if #[cfg(target_arch = "x86_64")] {
+        const DEFAULT_ID: i32 = 0x0803;
+        const ACCEPTS_FLAG_ELF: bool = false;
+    } else if #[cfg(target_arch = "aarch64")] {
+        const DEFAULT_ID: i32 = 0x0a03;
+        const ACCEPTS_FLAG_ELF: bool = true;
+    } else if #[cfg(all(target_arch = "arm", target_abi = "eabihf"))] {
+        const DEFAULT_ID: i32 = 0x0903;
+        const ACCEPTS_FLAG_ELF: bool = true;
+    } else if #[cfg(target_arch = "arm")] {
+        const DEFAULT_ID: i32 = 0x0b03;
+        const ACCEPTS_FLAG_ELF: bool = true;
+    } else if #[cfg(target_arch = "riscv64")] {
+        const DEFAULT_ID: i32 = 0x1003;
+        const ACCEPTS_FLAG_ELF: bool = true;
+    } else if #[cfg(target_arch = "powerpc64")] {
+        const DEFAULT_ID: i32 = 0x0503;
+        const ACCEPTS_FLAG_ELF: bool = true;
+    } else if #[cfg(target_arch = "s390x")] {
+        const DEFAULT_ID: i32 = 0x0403;
+        const ACCEPTS_FLAG_ELF: bool = true;
+    } else if #[cfg(target_arch = "sparc64")] {
+        const DEFAULT_ID: i32 = 0x0103;
+        const ACCEPTS_FLAG_ELF: bool = true;
+    } else if #[cfg(target_arch = "mips64")] {
+        const DEFAULT_ID: i32 = 0x0703;
+        const ACCEPTS_FLAG_ELF: bool = true;
+    } else if #[cfg(target_arch = "loongarch64")] {
+        const DEFAULT_ID: i32 = 0x1203;
+        const ACCEPTS_FLAG_ELF: bool = true;
+    } else {
+        const DEFAULT_ID: i32 = 0x0003;
+        const ACCEPTS_FLAG_ELF: bool = true;
+    }
+}
+
+/// The cache of library locations maintained by `ldconfig` and consulted by the GNU dynamic
+/// loader.
+///
+/// Both the format used since glibc 2.32 and the older `ld.so-1.7.0` format are understood. When
+/// a file contains both, only the newer one is used, just like the dynamic loader does.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use ::libloading::os::unix::LdSoCache;
+/// let cache = LdSoCache::read("/etc/ld.so.cache").unwrap();
+/// for entry in cache.entries() {
+///     println!("{:?} => {:?}", entry.name, entry.path);
+/// }
+/// println!("{:?}", cache.lookup("libc.so.6"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct LdSoCache {
+    path: PathBuf,
+    entries: Vec<CacheEntry>,
+}
+
+/// An entry of an [`LdSoCache`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CacheEntry {
+    /// The name the library is looked up by, usually its `SONAME`.
+    pub name: OsString,
+    /// The file the name refers to.
+    pub path: PathBuf,
+    /// The `FLAG_*` value describing the ABI of the library.
+    pub flags: i32,
+    /// The `hwcap` value of the entry. Always `0` in the old format.
+    pub hwcap: u64,
+    /// The `glibc-hwcaps` subdirectory the library lives in, e.g. `x86-64-v3`.
+    pub hwcaps: Option<OsString>,
+}
+
+impl LdSoCache {
+    /// Read and parse the cache at `filename`.
+    pub fn read(filename: impl AsFilename) -> Result<LdSoCache, Error> {
+        filename.posix_filename(|posix_filename| unsafe {
+            // SAFE: `posix_filename` is a null-terminated string.
+            LdSoCache::read_path(super::char_ptr_to_path(posix_filename))
+        })
+    }
+
+    fn read_path(path: &Path) -> Result<LdSoCache, Error> {
+        let data = std::fs::read(path).map_err(|source| Error::Io {
+            source: source.into(),
+        })?;
+        Ok(LdSoCache {
+            path: path.to_owned(),
+            entries: parse(&data)?,
+        })
+    }
+
+    /// The cache used by the dynamic loader, if there is one.
+    pub(super) fn system() -> Option<LdSoCache> {
+        if cfg!(all(target_os = "linux", target_env = "gnu")) {
+            LdSoCache::read_path(Path::new(SYSTEM_CACHE)).ok()
+        } else {
+            None
+        }
+    }
+
+    /// The path the cache has been read from.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// All the entries in the cache, in the order they are stored.
+    pub fn entries(&self) -> &[CacheEntry] {
+        &self.entries
+    }
+
+    /// Look up `name` the way the dynamic loader does.
+    ///
+    /// Entries built for a different ABI, as well as entries in `glibc-hwcaps` subdirectories
+    /// the processor does not support, are skipped. Among the remaining entries the one in the
+    /// most preferred `glibc-hwcaps` subdirectory wins, followed by the first regular entry.
+    ///
+    /// The returned file is not checked to exist.
+    pub fn lookup(&self, name: impl AsRef<OsStr>) -> Option<&Path> {
+        let name = name.as_ref();
+        let supported = supported_hwcaps();
+        let mut best: Option<(usize, &CacheEntry)> = None;
+        for entry in self.entries.iter().filter(|entry| entry.name == name) {
+            if entry.flags != DEFAULT_ID && !(ACCEPTS_FLAG_ELF && entry.flags == FLAG_ELF) {
+                continue;
+            }
+            let priority = match &entry.hwcaps {
+                Some(hwcaps) => match supported.iter().position(|s| OsStr::new(s) == hwcaps) {
+                    Some(priority) => priority,
+                    None => continue,
+                },
+                // Entries for the legacy hwcaps subdirectories are no longer used.
+                None if entry.hwcap != 0 => continue,
+                None => supported.len(),
+            };
+            if best.is_none_or(|(best, _)| priority < best) {
+                best = Some((priority, entry));
+            }
+        }
+        best.map(|(_, entry)| &*entry.path)
+    }
+
+    /// Find the file the dynamic loader would load for `name`, consulting this cache in place of
+    /// the system one.
+    ///
+    /// See [`resolve_library_name`] for details.
+    pub fn resolve_library_name(&self, name: impl AsFilename) -> Result<Option<PathBuf>, Error> {
+        resolve_bare_name(name, Some(self))
+    }
+
+    pub(super) fn lookup_loadable(&self, name: &OsStr) -> Option<PathBuf> {
+        self.lookup(name)
+            .filter(|path| is_loadable(path))
+            .map(Path::to_owned)
+    }
+}
+
+/// Find the file the dynamic loader would load for `name` without loading it.
+///
+/// This mirrors what `dlopen` called by this crate does. Names containing a `/` are interpreted as
+/// paths. Other names are looked up in the following locations:
+///
+/// 1. `DT_RPATH` of the executable, unless it has a `DT_RUNPATH`;
+/// 2. `LD_LIBRARY_PATH`;
+/// 3. `DT_RUNPATH` of the executable;
+/// 4. `/etc/ld.so.cache` (see [`LdSoCache::lookup`]);
+/// 5. The default directories, including the multiarch ones.
+///
+/// In each of the directories the `glibc-hwcaps` subdirectories supported by the processor are
+/// searched first. Files built for a different architecture are skipped.
+///
+/// `Ok(None)` is returned if no suitable file is found.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use ::libloading::os::unix::resolve_library_name;
+/// println!("{:?}", resolve_library_name("libc.so.6"));
+/// ```
+pub fn resolve_library_name(name: impl AsFilename) -> Result<Option<PathBuf>, Error> {
+    resolve_bare_name(name, LdSoCache::system().as_ref())
+}
+
+fn resolve_bare_name(
+    name: impl AsFilename,
+    cache: Option<&LdSoCache>,
+) -> Result<Option<PathBuf>, Error> {
+    name.posix_filename(|posix_name| unsafe {
+        // SAFE: `posix_name` is a null-terminated string.
+        let name = super::char_ptr_to_path(posix_name).as_os_str();
+        Ok(resolve_for_executable(name, cache))
+    })
+}
+
+/// The `glibc-hwcaps` subdirectories supported by the processor, most preferred first.
+pub(super) fn supported_hwcaps() -> &'static [&'static str] {
+    cfg_if::cfg_if! {
+        if #[cfg(all(target_arch = "x86_64", target_pointer_width = "64"))] {
+            use std::arch::is_x86_feature_detected as has;
+            let v2 = has!("cmpxchg16b")
+                && has!("popcnt")
+                && has!("sse3")
+                && has!("sse4.1")
+                && has!("sse4.2")
+                && has!("ssse3");
+            let v3 = v2
+                && has!("avx")
+                && has!("avx2")
+                && has!("bmi1")
+                && has!("bmi2")
+                && has!("f16c")
+                && has!("fma")
+                && has!("lzcnt")
+                && has!("movbe")
+                && has!("xsave");
+            let v4 = v3
+                && has!("avx512f")
+                && has!("avx512bw")
+                && has!("avx512cd")
+                && has!("avx512dq")
+                && has!("avx512vl");
+            const LEVELS: &[&str] = &["x86-64-v4", "x86-64-v3", "x86-64-v2"];
+            match (v4, v3, v2) {
+                (true, _, _) => LEVELS,
+                (_, true, _) => &LEVELS[1..],
+                (_, _, true) => &LEVELS[2..],
+                _ => &[],
+            }
+        } else if #[cfg(all(target_arch = "powerpc64", target_endian = "little"))] {
+            const AT_HWCAP2: core::ffi::c_ulong = 26;
+            const PPC_FEATURE2_ARCH_3_00: core::ffi::c_ulong = 0x0080_0000;
+            const PPC_FEATURE2_ARCH_3_1: core::ffi::c_ulong = 0x0004_0000;
+            extern "C" {
+                fn getauxval(kind: core::ffi::c_ulong) -> core::ffi::c_ulong;
+            }
+            const LEVELS: &[&str] = &["power10", "power9"];
+            let hwcap2 = unsafe { getauxval(AT_HWCAP2) };
+            if hwcap2 & PPC_FEATURE2_ARCH_3_1 != 0 {
+                LEVELS
+            } else if hwcap2 & PPC_FEATURE2_ARCH_3_00 != 0 {
+                &LEVELS[1..]
+            } else {
+                &[]
+            }
+        } else {
+            &[]
+        }
+    }
+}
+
+fn parse(data: &[u8]) -> Result<Vec<CacheEntry>, Error> {
+    if data.starts_with(NEW_MAGIC) {
+        return parse_new(data);
+    }
+    if !data.starts_with(OLD_MAGIC) {
+        return Err(Error::MalformedLdCache);
+    }
+    let count = usize::try_from(read_u32(data, 12)?).map_err(|_| Error::MalformedLdCache)?;
+    let strings = count
+        .checked_mul(OLD_ENTRY_SIZE)
+        .and_then(|size| size.checked_add(OLD_HEADER_SIZE))
+        .filter(|&end| end <= data.len())
+        .ok_or(Error::MalformedLdCache)?;
+    let new = strings.next_multiple_of(NEW_ALIGNMENT);
+    if data
+        .get(new..)
+        .is_some_and(|new| new.starts_with(NEW_MAGIC))
+    {
+        return parse_new(&data[new..]);
+    }
+    (0..count)
+        .map(|index| {
+            let entry = OLD_HEADER_SIZE + index * OLD_ENTRY_SIZE;
+            Ok(CacheEntry {
+                flags: read_u32(data, entry)? as i32,
+                name: read_string(data, strings, read_u32(data, entry + 4)?)?.to_owned(),
+                path: read_string(data, strings, read_u32(data, entry + 8)?)?.into(),
+                hwcap: 0,
+                hwcaps: None,
+            })
+        })
+        .collect()
+}
+
+fn parse_new(data: &[u8]) -> Result<Vec<CacheEntry>, Error> {
+    let native = if cfg!(target_endian = "little") {
+        ENDIAN_LITTLE
+    } else {
+        ENDIAN_BIG
+    };
+    let endian = *data.get(28).ok_or(Error::MalformedLdCache)? & ENDIAN_MASK;
+    if endian != 0 && endian != native {
+        return Err(Error::MalformedLdCache);
+    }
+    let count = usize::try_from(read_u32(data, 20)?).map_err(|_| Error::MalformedLdCache)?;
+    count
+        .checked_mul(NEW_ENTRY_SIZE)
+        .and_then(|size| size.checked_add(NEW_HEADER_SIZE))
+        .filter(|&end| end <= data.len())
+        .ok_or(Error::MalformedLdCache)?;
+    let hwcaps = hwcaps_names(data, read_u32(data, 32)?)?;
+    (0..count)
+        .map(|index| {
+            let entry = NEW_HEADER_SIZE + index * NEW_ENTRY_SIZE;
+            let hwcap = read_u64(data, entry + 16)?;
+            let hwcaps = if hwcap >> 32 == HWCAP_EXTENSION >> 32 {
+                let name = hwcaps
+                    .get(hwcap as u32 as usize)
+                    .ok_or(Error::MalformedLdCache)?;
+                Some((*name).to_owned())
+            } else {
+                None
+            };
+            Ok(CacheEntry {
+                flags: read_u32(data, entry)? as i32,
+                name: read_string(data, 0, read_u32(data, entry + 4)?)?.to_owned(),
+                path: read_string(data, 0, read_u32(data, entry + 8)?)?.into(),
+                hwcap,
+                hwcaps,
+            })
+        })
+        .collect()
+}
+
+/// Names of the `glibc-hwcaps` subdirectories, as listed in the extensions at `offset`.
+fn hwcaps_names(data: &[u8], offset: u32) -> Result<Vec<&OsStr>, Error> {
+    let offset = offset as usize;
+    if offset == 0 || read_u32(data, offset).ok() != Some(EXTENSION_MAGIC) {
+        return Ok(Vec::new());
+    }
+    let count = read_u32(data, offset + 4)? as usize;
+    for section in (0..count).map(|index| offset + 8 + index * 16) {
+        if read_u32(data, section)? != EXTENSION_TAG_GLIBC_HWCAPS {
+            continue;
+        }
+        let start = read_u32(data, section + 8)? as usize;
+        let size = read_u32(data, section + 12)? as usize;
+        return (0..size / 4)
+            .map(|index| read_string(data, 0, read_u32(data, start + index * 4)?))
+            .collect();
+    }
+    Ok(Vec::new())
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, Error> {
+    data.get(offset..offset.checked_add(4).ok_or(Error::MalformedLdCache)?)
+        .map(|bytes| u32::from_ne_bytes(bytes.try_into().unwrap()))
+        .ok_or(Error::MalformedLdCache)
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Result<u64, Error> {
+    data.get(offset..offset.checked_add(8).ok_or(Error::MalformedLdCache)?)
+        .map(|bytes| u64::from_ne_bytes(bytes.try_into().unwrap()))
+        .ok_or(Error::MalformedLdCache)
+}
+
+/// The null-terminated string at `offset` from `base`.
+fn read_string(data: &[u8], base: usize, offset: u32) -> Result<&OsStr, Error> {
+    let start = base
+        .checked_add(offset as usize)
+        .ok_or(Error::MalformedLdCache)?;
+    let bytes = data.get(start..).ok_or(Error::MalformedLdCache)?;
+    let end = bytes
+        .iter()
+        .position(|&b| b == 0)
+        .ok_or(Error::MalformedLdCache)?;
+    Ok(OsStr::from_bytes(&bytes[..end]))
+}
@@ -1,12 +1,72 @@
 pub use self::consts::*;
+#[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+pub use self::compat::{CompatibilityReport, VersionRequirement};
+#[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+pub use self::diagnose::{diagnose_load_failure, LoadDiagnosis, MissingDependency};
+#[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+pub use self::dynamic::{
+    Dependency, DynamicSection, SearchKind, SearchLocation, DF_1_GLOBAL, DF_1_INITFIRST,
+    DF_1_INTERPOSE, DF_1_NODEFLIB, DF_1_NODELETE, DF_1_NOOPEN, DF_1_NOW, DF_1_ORIGIN, DF_1_PIE,
+    DF_BIND_NOW, DF_ORIGIN, DF_STATIC_TLS, DF_SYMBOLIC, DF_TEXTREL,
+};
+#[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+pub use self::ld_cache::{resolve_library_name, CacheEntry, LdSoCache};
+#[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+pub use crate::error::Incompatibility;
+#[cfg(all(
+    feature = "std",
+    any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "freebsd",
+        target_os = "dragonfly",
+        target_os = "openbsd",
+        target_os = "netbsd"
+    )
+))]
+pub use self::probe::{probe_load, probe_load_with_symbols, ProbeOutcome};
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(crate) use self::dynsym::symbol_size;
+use self::backend::{Active, Backend};
 use crate::as_filename::AsFilename;
 use crate::as_symbol_name::AsSymbolName;
+use crate::observer::{self, LibraryName, SymbolKey};
+use crate::trace::traced;
 use crate::util::ensure_compatible_types;
 use core::ffi::CStr;
 use core::ptr::null;
 use core::{fmt, marker, mem, ptr};
 
+pub(crate) mod backend;
+#[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+mod compat;
 mod consts;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod dynsym;
+#[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+mod diagnose;
+#[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+mod dynamic;
+#[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+mod elf;
+#[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+mod ld_cache;
+// `probe` hardcodes constants and the layout of the wait status, which have been checked for
+// these targets only.
+#[cfg(all(
+    feature = "std",
+    any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "freebsd",
+        target_os = "dragonfly",
+        target_os = "openbsd",
+        target_os = "netbsd"
+    )
+))]
+mod probe;
 
 /// Run code and handle errors reported by `dlerror`.
 ///
@@ -60,7 +120,7 @@ where
     // or a bug in implementation of dl* family of functions.
     closure().ok_or_else(|| unsafe {
         // This code will only get executed if the `closure` returns `None`.
-        let dlerror_str = dlerror();
+        let dlerror_str = Active::dlerror();
         if dlerror_str.is_null() {
             // In non-dlsym case this may happen when there’re bugs in our bindings or there’s
             // non-libloading user of libdl; possibly in another thread.
@@ -182,28 +242,105 @@ impl Library {
         filename.posix_filename(|posix_filename| Library::open_char_ptr(posix_filename, flags))
     }
 
+    /// Check whether the library file at `filename` can be loaded into the running process.
+    ///
+    /// The ELF header of the file is compared against the running process' word size, byte order,
+    /// machine and OS ABI. Symbol versions the library requires from its dependencies (as listed
+    /// in its `.gnu.version_r` section) are compared against those provided by the copies of these
+    /// dependencies already loaded into the running process.
+    ///
+    /// The file is only read; no initialisation routines are executed. A `filename` without a `/`
+    /// is looked up with [`resolve_library_name`] first, failing with
+    /// [`Error::LibraryNotFound`](crate::Error::LibraryNotFound) if it is not found.
+    #[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+    #[cfg_attr(
+        libloading_docs,
+        doc(cfg(all(feature = "std", any(target_os = "linux", target_os = "android"))))
+    )]
+    pub fn check_compatible(filename: impl AsFilename) -> Result<CompatibilityReport, crate::Error> {
+        filename.posix_filename(|posix_filename| unsafe {
+            // SAFE: `posix_filename` is a null-terminated string.
+            compat::check(&dynamic::locate(char_ptr_to_path(posix_filename))?)
+        })
+    }
+
+    /// Find and load a shared library after checking that it is compatible with the running
+    /// process.
+    ///
+    /// This is equivalent to [`Library::open`], except that [`Library::check_compatible`] is
+    /// consulted first. If any incompatibilities are found, [`Error::Incompatible`] describing
+    /// them is returned instead of the less descriptive error reported by `dlopen`.
+    ///
+    /// [`Error::Incompatible`]: crate::Error::Incompatible
+    ///
+    /// # Safety
+    ///
+    /// When a library is loaded, initialisation routines contained within the library are executed.
+    /// For the purposes of safety, the execution of these routines is conceptually the same calling an
+    /// unknown foreign function and may impose arbitrary requirements on the caller for the call
+    /// to be sound.
+    ///
+    /// Additionally, the callers of this function must also ensure that execution of the
+    /// termination routines contained within the library is safe as well. These routines may be
+    /// executed when the library is unloaded.
+    #[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+    #[cfg_attr(
+        libloading_docs,
+        doc(cfg(all(feature = "std", any(target_os = "linux", target_os = "android"))))
+    )]
+    pub unsafe fn open_checked(
+        filename: impl AsFilename,
+        flags: core::ffi::c_int,
+    ) -> Result<Library, crate::Error> {
+        filename.posix_filename(|posix_filename| {
+            let report = compat::check(&dynamic::locate(char_ptr_to_path(posix_filename))?)?;
+            if !report.is_compatible() {
+                return Err(crate::Error::Incompatible {
+                    source: crate::error::IncompatibleLibrary(report.incompatibilities),
+                });
+            }
+            Library::open_char_ptr(posix_filename, flags)
+        })
+    }
+
     /// private helper to call dlopen+dlerror once we de-tangled the string into a raw pointer to a 0 terminated utf-8 string.
     /// caller must ensure that the string is actually 0 terminated.
     unsafe fn open_char_ptr(
         filename: *const core::ffi::c_char,
         flags: core::ffi::c_int,
     ) -> Result<Library, crate::Error> {
-        with_dlerror(
-            move || {
-                let result = dlopen(filename, flags);
+        let name = || {
+            if filename.is_null() {
+                LibraryName::This
+            } else {
+                LibraryName::Posix(CStr::from_ptr(filename))
+            }
+        };
+        observer::notify(|observer| observer.before_load(name()));
+        let result = traced!(
+            "dlopen",
+            { filename = ?crate::trace::Name(filename), flags },
+            with_dlerror(
+                move || {
+                    let result = Active::dlopen(filename, flags);
 
-                // ensure filename lives until dlopen completes
-                if result.is_null() {
-                    None
-                } else {
-                    Some(Library { handle: result })
-                }
-            },
-            |desc| crate::Error::DlOpen {
-                source: desc.into(),
-            },
-        )
-        .map_err(|e| e.unwrap_or(crate::Error::DlOpenUnknown))
+                    // ensure filename lives until dlopen completes
+                    if result.is_null() {
+                        None
+                    } else {
+                        Some(Library { handle: result })
+                    }
+                },
+                |desc| crate::Error::DlOpen {
+                    source: desc.into(),
+                },
+            )
+            .map_err(|e| e.unwrap_or(crate::Error::DlOpenUnknown))
+        );
+        observer::notify(|observer| {
+            observer.after_load(name(), result.as_ref().map(|library| library.handle))
+        });
+        result
     }
 
     unsafe fn get_impl<T, F>(
@@ -222,28 +359,42 @@ impl Library {
         // We try to leave as little space as possible for this to occur, but we can’t exactly
         // fully prevent it.
         symbol.symbol_name(|posix_symbol| {
-            let result = with_dlerror(
-                || {
-                    dlerror();
-                    let symbol = dlsym(self.handle, posix_symbol);
-                    if symbol.is_null() {
-                        None
-                    } else {
-                        Some(Symbol {
-                            pointer: symbol,
-                            pd: marker::PhantomData,
-                        })
+            let result = traced!(
+                "dlsym",
+                { library = ?self.handle, symbol = ?crate::trace::Name(posix_symbol) },
+                {
+                    let result = with_dlerror(
+                        || {
+                            Active::dlerror();
+                            let symbol = Active::dlsym(self.handle, posix_symbol);
+                            if symbol.is_null() {
+                                None
+                            } else {
+                                Some(Symbol {
+                                    pointer: symbol,
+                                    pd: marker::PhantomData,
+                                })
+                            }
+                        },
+                        |desc| crate::Error::DlSym {
+                            source: desc.into(),
+                        },
+                    );
+                    match result {
+                        Err(None) => on_null(),
+                        Err(Some(e)) => Err(e),
+                        Ok(x) => Ok(x),
                     }
-                },
-                |desc| crate::Error::DlSym {
-                    source: desc.into(),
-                },
+                }
             );
-            match result {
-                Err(None) => on_null(),
-                Err(Some(e)) => Err(e),
-                Ok(x) => Ok(x),
-            }
+            observer::notify(|observer| {
+                observer.after_get(
+                    self.handle,
+                    SymbolKey::Name(CStr::from_ptr(posix_symbol)),
+                    result.as_ref().map(|symbol| symbol.pointer),
+                )
+            });
+            result
         })
     }
 
@@ -350,6 +501,78 @@ impl Library {
         Library { handle }
     }
 
+    /// Pin the library into memory, so that it is never unloaded.
+    ///
+    /// Looks up the name of the library with `dlinfo(handle, RTLD_DI_LINKMAP, _)` and opens it
+    /// again with `RTLD_NOLOAD | RTLD_NODELETE`, which marks the already loaded library as not
+    /// unloadable.
+    ///
+    /// If successful, the library will remain in memory even once every handle to it, including
+    /// this `Library`, has been closed with `dlclose`.
+    #[cfg(any(target_os = "linux", libloading_docs))]
+    #[cfg_attr(libloading_docs, doc(cfg(target_os = "linux")))]
+    pub fn pin(&self) -> Result<(), crate::Error> {
+        // The values are the same for glibc and musl.
+        const RTLD_NOLOAD: core::ffi::c_int = 0x4;
+        const RTLD_NODELETE: core::ffi::c_int = 0x1000;
+        const RTLD_DI_LINKMAP: core::ffi::c_int = 2;
+        unsafe {
+            let mut link_map: *const LinkMap = ptr::null();
+            with_dlerror(
+                || {
+                    if Active::dlinfo(self.handle, RTLD_DI_LINKMAP, (&raw mut link_map).cast()) == 0
+                    {
+                        Some(())
+                    } else {
+                        None
+                    }
+                },
+                |desc| crate::Error::DlInfo {
+                    source: desc.into(),
+                },
+            )
+            .map_err(|e| e.unwrap_or(crate::Error::DlInfoUnknown))?;
+            // The main program has an empty name, but it can only be opened with a null filename.
+            let name = (*link_map).l_name;
+            let name = if name.is_null() || *name == 0 {
+                null()
+            } else {
+                name
+            };
+            // The extra reference is not a `Library` of its own, so that it is not reported to the
+            // observers or traced. Closing it does not unload the library, as it is now marked
+            // with `RTLD_NODELETE`.
+            let handle = with_dlerror(
+                || {
+                    let handle =
+                        Active::dlopen(name, RTLD_LAZY | RTLD_NOLOAD | RTLD_NODELETE);
+                    if handle.is_null() {
+                        None
+                    } else {
+                        Some(handle)
+                    }
+                },
+                |desc| crate::Error::DlOpen {
+                    source: desc.into(),
+                },
+            )
+            .map_err(|e| e.unwrap_or(crate::Error::DlOpenUnknown))?;
+            with_dlerror(
+                || {
+                    if Active::dlclose(handle) == 0 {
+                        Some(())
+                    } else {
+                        None
+                    }
+                },
+                |desc| crate::Error::DlClose {
+                    source: desc.into(),
+                },
+            )
+            .map_err(|e| e.unwrap_or(crate::Error::DlCloseUnknown))
+        }
+    }
+
     /// Unload the library.
     ///
     /// This method might be a no-op, depending on the flags with which the `Library` was opened,
@@ -361,31 +584,76 @@ impl Library {
     ///
     /// The underlying data structures may still get leaked if an error does occur.
     pub fn close(self) -> Result<(), crate::Error> {
-        let result = with_dlerror(
-            || {
-                if unsafe { dlclose(self.handle) } == 0 {
-                    Some(())
-                } else {
-                    None
-                }
-            },
-            |desc| crate::Error::DlClose {
-                source: desc.into(),
-            },
-        )
-        .map_err(|e| e.unwrap_or(crate::Error::DlCloseUnknown));
+        let result = self.close_handle();
+        observer::notify(|observer| observer.after_close(self.handle, result.as_ref().copied()));
         // While the library is not free'd yet in case of an error, there is no reason to try
         // dropping it again, because all that will do is try calling `dlclose` again. only
         // this time it would ignore the return result, which we already seen failing…
         mem::forget(self);
         result
     }
+
+    /// Call `dlclose`, without consuming the `Library`.
+    fn close_handle(&self) -> Result<(), crate::Error> {
+        traced!(
+            "dlclose",
+            { library = ?self.handle },
+            with_dlerror(
+                || {
+                    if unsafe { Active::dlclose(self.handle) } == 0 {
+                        Some(())
+                    } else {
+                        None
+                    }
+                },
+                |desc| crate::Error::DlClose {
+                    source: desc.into(),
+                },
+            )
+            .map_err(|e| e.unwrap_or(crate::Error::DlCloseUnknown))
+        )
+    }
+}
+
+/// View a null-terminated filename as a `Path`.
+///
+/// # Safety
+///
+/// `filename` must point to a null-terminated string that outlives the returned reference.
+#[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+unsafe fn char_ptr_to_path<'a>(filename: *const core::ffi::c_char) -> &'a std::path::Path {
+    use std::os::unix::ffi::OsStrExt;
+    std::path::Path::new(std::ffi::OsStr::from_bytes(
+        CStr::from_ptr(filename).to_bytes(),
+    ))
+}
+
+impl crate::symbol_source::Sealed for Library {}
+impl crate::SymbolSource for Library {
+    type Symbol<'lib, T: 'lib> = Symbol<T>;
+    type Handle = *mut core::ffi::c_void;
+
+    unsafe fn get<T>(&self, symbol: impl AsSymbolName) -> Result<Symbol<T>, crate::Error> {
+        Library::get(self, symbol)
+    }
+
+    unsafe fn get_singlethreaded<T>(
+        &self,
+        symbol: impl AsSymbolName,
+    ) -> Result<Symbol<T>, crate::Error> {
+        Library::get_singlethreaded(self, symbol)
+    }
+
+    fn handle(&self) -> *mut core::ffi::c_void {
+        self.handle
+    }
 }
 
 impl Drop for Library {
     fn drop(&mut self) {
-        unsafe {
-            dlclose(self.handle);
+        let result = self.close_handle();
+        if observer::is_observed() {
+            observer::notify(|observer| observer.after_drop(self.handle, result.as_ref().copied()));
         }
     }
 }
@@ -406,6 +674,15 @@ pub struct Symbol<T> {
 }
 
 impl<T> Symbol<T> {
+    /// A symbol with the address `pointer`, for [`StaticLibrary`](crate::StaticLibrary).
+    #[cfg(feature = "alloc")]
+    pub(crate) unsafe fn from_ptr(pointer: *mut core::ffi::c_void) -> Symbol<T> {
+        Symbol {
+            pointer,
+            pd: marker::PhantomData,
+        }
+    }
+
     /// Convert the loaded `Symbol` into a raw pointer.
     pub fn into_raw(self) -> *mut core::ffi::c_void {
         self.pointer
@@ -500,6 +777,20 @@ extern "C" {
     ) -> *mut core::ffi::c_void;
     fn dlerror() -> *mut core::ffi::c_char;
     fn dladdr(addr: *mut core::ffi::c_void, info: *mut DlInfo) -> core::ffi::c_int;
+    #[cfg(any(target_os = "linux", libloading_docs))]
+    fn dlinfo(
+        handle: *mut core::ffi::c_void,
+        request: core::ffi::c_int,
+        info: *mut core::ffi::c_void,
+    ) -> core::ffi::c_int;
+}
+
+/// The leading fields of `struct link_map`, which are the same for glibc and musl.
+#[cfg(any(target_os = "linux", libloading_docs))]
+#[repr(C)]
+struct LinkMap {
+    l_addr: usize,
+    l_name: *const core::ffi::c_char,
 }
 
 #[repr(C)]
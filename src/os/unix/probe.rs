@@ -0,0 +1,354 @@
+use super::{dlerror, dlopen, dlsym, RTLD_LOCAL, RTLD_NOW};
+use crate::as_filename::AsFilename;
+use crate::as_symbol_name::AsSymbolName;
+use crate::Error;
+use alloc::ffi::CString;
+use alloc::vec::Vec;
+use core::ffi::{c_char, c_int, c_short, c_void, CStr};
+use std::io::Read;
+use std::os::fd::AsRawFd;
+use std::time::{Duration, Instant};
+
+/// The outcome of loading a library in a separate process, produced by [`probe_load`].
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum ProbeOutcome {
+    /// The library has been loaded and all of the requested symbols have been found.
+    Loaded,
+    /// The library could not be loaded.
+    LoadFailed {
+        /// The error `dlopen` reported.
+        error: Error,
+    },
+    /// The library has been loaded, but one of the requested symbols could not be found.
+    SymbolNotFound {
+        /// The index of the symbol in the list of requested symbols.
+        index: usize,
+        /// The error `dlsym` reported.
+        error: Error,
+    },
+    /// The process has been terminated by a signal, e.g. `SIGSEGV` when an initialisation
+    /// routine crashed.
+    Signaled {
+        /// The number of the signal.
+        signal: c_int,
+    },
+    /// The process has exited before reporting the result, e.g. because an initialisation
+    /// routine called `exit`.
+    Exited {
+        /// The exit status of the process.
+        code: c_int,
+    },
+    /// The process has not finished in time and has been killed.
+    TimedOut,
+}
+
+impl ProbeOutcome {
+    /// Whether the library has been loaded and all of the requested symbols have been found.
+    pub fn is_loaded(&self) -> bool {
+        matches!(self, ProbeOutcome::Loaded)
+    }
+}
+
+/// Find out whether loading a library crashes or hangs, without loading it into this process.
+///
+/// Equivalent to [`probe_load_with_symbols`] without any symbols to look up.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use ::libloading::os::unix::probe_load;
+/// # use std::time::Duration;
+/// let outcome = probe_load("/path/to/awesome.module", Duration::from_secs(5)).unwrap();
+/// if outcome.is_loaded() {
+///     // Still unsafe, the initialisation routines may behave differently the second time.
+///     let library = unsafe { libloading::Library::new("/path/to/awesome.module") };
+/// }
+/// ```
+pub fn probe_load(filename: impl AsFilename, timeout: Duration) -> Result<ProbeOutcome, Error> {
+    probe_load_with_symbols(filename, [] as [&str; 0], timeout)
+}
+
+/// Find out whether loading a library and looking up symbols in it crashes or hangs, without
+/// loading it into this process.
+///
+/// The process is forked, and the child process loads the library with
+/// <code>[RTLD_NOW] | [RTLD_LOCAL]</code>, so that missing symbols the library depends on are
+/// detected as well, and looks up each of the `symbols`. The parent waits for the child to report
+/// the result for at most `timeout`, after which the child is killed with `SIGKILL`.
+///
+/// An `Err` is returned only if the child process could not be started or waited for. Everything
+/// that happens in the child is reported as a [`ProbeOutcome`].
+///
+/// # Notes
+///
+/// Only the calling thread exists in the child process. If another thread held a lock, such as
+/// the lock of the allocator or of the dynamic loader, at the time of the fork, loading the
+/// library in the child may deadlock and be reported as [`ProbeOutcome::TimedOut`]. Handlers
+/// registered with `pthread_atfork` run in the child, as do handlers registered with `atexit` if
+/// an initialisation routine calls `exit`.
+///
+/// A successful probe does not guarantee that loading the library into this process succeeds:
+/// the initialisation routines may depend on state that differs between the processes.
+pub fn probe_load_with_symbols<S: AsSymbolName>(
+    filename: impl AsFilename,
+    symbols: impl IntoIterator<Item = S>,
+    timeout: Duration,
+) -> Result<ProbeOutcome, Error> {
+    // Everything that allocates must happen before the fork.
+    let symbols = symbols
+        .into_iter()
+        .map(|symbol| {
+            symbol.symbol_name(|posix_symbol| unsafe {
+                // SAFE: `posix_symbol` is a null-terminated string.
+                Ok(CString::from(CStr::from_ptr(posix_symbol)))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let symbols = symbols.iter().map(|s| s.as_ptr()).collect::<Vec<_>>();
+    filename.posix_filename(|posix_filename| {
+        let deadline = Instant::now() + timeout;
+        let (mut reader, writer) = std::io::pipe().map_err(io_error)?;
+        let pid = unsafe { fork() };
+        if pid < 0 {
+            return Err(io_error(std::io::Error::last_os_error()));
+        }
+        if pid == 0 {
+            unsafe { probe_child(posix_filename, &symbols, writer.as_raw_fd()) }
+        }
+        drop(writer);
+        match wait_child(pid, &mut reader, deadline)? {
+            Some((status, report)) => Ok(outcome(status, &report)),
+            None => Ok(ProbeOutcome::TimedOut),
+        }
+    })
+}
+
+const REPORT_LOADED: u8 = 0;
+const REPORT_LOAD_FAILED: u8 = 1;
+const REPORT_SYMBOL_NOT_FOUND: u8 = 2;
+
+/// The time the child is first waited for before checking whether it has exited.
+const MIN_WAIT_INTERVAL: Duration = Duration::from_millis(1);
+/// The longest time the child is waited for before checking whether it has exited.
+const MAX_WAIT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Load the library and report the result to the parent through `fd`.
+///
+/// Runs in the forked child, and must therefore only call functions that do not allocate or take
+/// locks another thread may have held. `dlopen` itself is an exception, as it is what is probed.
+unsafe fn probe_child(filename: *const c_char, symbols: &[*const c_char], fd: c_int) -> ! {
+    let handle = dlopen(filename, RTLD_NOW | RTLD_LOCAL);
+    if handle.is_null() {
+        write_report(fd, REPORT_LOAD_FAILED, 0, dlerror());
+        _exit(0);
+    }
+    for (index, &symbol) in symbols.iter().enumerate() {
+        // See `Library::get_impl` for why `dlerror` is called twice.
+        dlerror();
+        if dlsym(handle, symbol).is_null() {
+            let error = dlerror();
+            if !error.is_null() {
+                write_report(fd, REPORT_SYMBOL_NOT_FOUND, index as u64, error);
+                _exit(0);
+            }
+        }
+    }
+    write_report(fd, REPORT_LOADED, 0, core::ptr::null());
+    _exit(0)
+}
+
+/// Write a report of a tag byte, an index, and a `dlerror` message, if any.
+unsafe fn write_report(fd: c_int, tag: u8, index: u64, message: *const c_char) {
+    write_all(fd, &[tag]);
+    write_all(fd, &index.to_ne_bytes());
+    if !message.is_null() {
+        write_all(fd, CStr::from_ptr(message).to_bytes());
+    }
+}
+
+unsafe fn write_all(fd: c_int, mut bytes: &[u8]) {
+    while !bytes.is_empty() {
+        let written = write(fd, bytes.as_ptr().cast(), bytes.len());
+        if written <= 0 {
+            // The parent reports an incomplete report as `Exited`.
+            _exit(1);
+        }
+        bytes = &bytes[written as usize..];
+    }
+}
+
+/// Wait for the child to exit until the deadline passes, and kill it afterwards, collecting its
+/// report in the meantime.
+///
+/// `None` if the child has been killed. The end of the pipe does not mark the end of the report:
+/// a child forked by another thread at the same time may have inherited the writing end, and
+/// keep it open for as long as it runs. The child is therefore checked on in intervals, which
+/// start short, for libraries that load quickly, and grow up to [`MAX_WAIT_INTERVAL`] while the
+/// pipe is open. Waiting for an interval ends early when something is written to the pipe or it
+/// is closed.
+fn wait_child(
+    pid: c_int,
+    reader: &mut std::io::PipeReader,
+    deadline: Instant,
+) -> Result<Option<(c_int, Vec<u8>)>, Error> {
+    let mut report = Vec::new();
+    let mut open = true;
+    let mut interval = MIN_WAIT_INTERVAL;
+    loop {
+        let mut status = 0;
+        match unsafe { waitpid(pid, &mut status, WNOHANG) } {
+            0 => {}
+            result if result < 0 => {
+                let error = std::io::Error::last_os_error();
+                if error.kind() != std::io::ErrorKind::Interrupted {
+                    return Err(io_error(error));
+                }
+            }
+            _ => {
+                // Everything the child has written is in the pipe by now.
+                if open {
+                    read_report(reader, &mut report, Duration::ZERO)?;
+                }
+                return Ok(Some((status, report)));
+            }
+        }
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        let wait = remaining.min(interval);
+        if open {
+            open = read_report(reader, &mut report, wait)?;
+            // Once the pipe has been closed, the child is about to exit.
+            interval = if open {
+                (interval * 2).min(MAX_WAIT_INTERVAL)
+            } else {
+                MIN_WAIT_INTERVAL
+            };
+        } else {
+            std::thread::sleep(wait);
+        }
+    }
+    unsafe {
+        kill(pid, SIGKILL);
+        let mut status = 0;
+        while waitpid(pid, &mut status, 0) < 0 {
+            let error = std::io::Error::last_os_error();
+            if error.kind() != std::io::ErrorKind::Interrupted {
+                return Err(io_error(error));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Read what has been written to the pipe, waiting at most `timeout` for anything to be written.
+///
+/// `false` once the pipe has been closed.
+fn read_report(
+    reader: &mut std::io::PipeReader,
+    report: &mut Vec<u8>,
+    mut timeout: Duration,
+) -> Result<bool, Error> {
+    let mut buffer = [0; 256];
+    loop {
+        let mut poll_fd = PollFd {
+            fd: reader.as_raw_fd(),
+            events: POLLIN,
+            revents: 0,
+        };
+        let millis = c_int::try_from(timeout.as_millis()).unwrap_or(c_int::MAX);
+        match unsafe { poll(&mut poll_fd, 1, millis) } {
+            0 => return Ok(true),
+            result if result < 0 => {
+                let error = std::io::Error::last_os_error();
+                if error.kind() == std::io::ErrorKind::Interrupted {
+                    continue;
+                }
+                return Err(io_error(error));
+            }
+            _ => match reader.read(&mut buffer) {
+                Ok(0) => return Ok(false),
+                Ok(read) => {
+                    report.extend_from_slice(&buffer[..read]);
+                    // Only read what is available from now on.
+                    timeout = Duration::ZERO;
+                }
+                Err(error) if error.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(error) => return Err(io_error(error)),
+            },
+        }
+    }
+}
+
+fn outcome(status: c_int, report: &[u8]) -> ProbeOutcome {
+    // The layout of the status is the same on all of the supported targets.
+    let signal = status & 0x7f;
+    if signal != 0 {
+        return ProbeOutcome::Signaled { signal };
+    }
+    let code = (status >> 8) & 0xff;
+    let (tag, index, message) = match report {
+        [tag, rest @ ..] if code == 0 && rest.len() >= 8 => {
+            let (index, message) = rest.split_at(8);
+            let index = u64::from_ne_bytes(index.try_into().unwrap());
+            (*tag, index as usize, message)
+        }
+        _ => return ProbeOutcome::Exited { code },
+    };
+    let message = CString::new(message).ok();
+    match tag {
+        REPORT_LOADED => ProbeOutcome::Loaded,
+        REPORT_LOAD_FAILED => ProbeOutcome::LoadFailed {
+            error: match message {
+                Some(message) => Error::DlOpen {
+                    source: crate::error::DlError(message),
+                },
+                None => Error::DlOpenUnknown,
+            },
+        },
+        REPORT_SYMBOL_NOT_FOUND => ProbeOutcome::SymbolNotFound {
+            index,
+            error: match message {
+                Some(message) => Error::DlSym {
+                    source: crate::error::DlError(message),
+                },
+                None => Error::DlSymUnknown,
+            },
+        },
+        _ => ProbeOutcome::Exited { code },
+    }
+}
+
+fn io_error(source: std::io::Error) -> Error {
+    Error::Io {
+        source: source.into(),
+    }
+}
+
+#[repr(C)]
+struct PollFd {
+    fd: c_int,
+    events: c_short,
+    revents: c_short,
+}
+
+// The values are the same on all of the targets the module is compiled for.
+const POLLIN: c_short = 1;
+const WNOHANG: c_int = 1;
+const SIGKILL: c_int = 9;
+
+#[cfg(target_os = "linux")]
+type NfdsT = core::ffi::c_ulong;
+#[cfg(not(target_os = "linux"))]
+type NfdsT = core::ffi::c_uint;
+
+extern "C" {
+    fn fork() -> c_int;
+    fn _exit(status: c_int) -> !;
+    fn write(fd: c_int, buf: *const c_void, count: usize) -> isize;
+    fn poll(fds: *mut PollFd, nfds: NfdsT, timeout: c_int) -> c_int;
+    fn waitpid(pid: c_int, status: *mut c_int, options: c_int) -> c_int;
+    fn kill(pid: c_int, signal: c_int) -> c_int;
+}
@@ -17,7 +17,10 @@ mod windows_imports {
 use self::windows_imports::*;
 use crate::as_filename::AsFilename;
 use crate::as_symbol_name::AsSymbolName;
+use crate::observer::{self, LibraryName, SymbolKey};
+use crate::trace::traced;
 use crate::util::ensure_compatible_types;
+use core::ffi::{c_void, CStr};
 use core::{fmt, marker, mem, ptr};
 
 /// The platform-specific counterpart of the cross-platform [`Library`](crate::Library).
@@ -161,20 +164,39 @@ impl Library {
     ) -> Result<Library, crate::Error> {
         filename.windows_filename(|windows_filename| {
             let _guard = ErrorModeGuard::new();
-            with_get_last_error(
-                |source| crate::Error::LoadLibraryExW { source },
-                || {
-                    // Make sure no winapi calls as a result of drop happen inside this closure, because
-                    // otherwise that might change the return value of the GetLastError.
-                    let handle = LoadLibraryExW(windows_filename, 0, flags);
-                    if handle == 0 {
-                        None
-                    } else {
-                        Some(Library(handle))
-                    }
-                },
-            )
-            .map_err(|e| e.unwrap_or(crate::Error::LoadLibraryExWUnknown))
+            let name = || {
+                let mut length = 0;
+                while *windows_filename.add(length) != 0 {
+                    length += 1;
+                }
+                LibraryName::Windows(core::slice::from_raw_parts(windows_filename, length))
+            };
+            observer::notify(|observer| observer.before_load(name()));
+            let result = traced!(
+                "LoadLibraryExW",
+                { filename = ?crate::trace::WideName(windows_filename), flags },
+                with_get_last_error(
+                    |source| crate::Error::LoadLibraryExW { source },
+                    || {
+                        // Make sure no winapi calls as a result of drop happen inside this closure, because
+                        // otherwise that might change the return value of the GetLastError.
+                        let handle = LoadLibraryExW(windows_filename, 0, flags);
+                        if handle == 0 {
+                            None
+                        } else {
+                            Some(Library(handle))
+                        }
+                    },
+                )
+                .map_err(|e| e.unwrap_or(crate::Error::LoadLibraryExWUnknown))
+            );
+            observer::notify(|observer| {
+                observer.after_load(
+                    name(),
+                    result.as_ref().map(|library| library.0 as *mut c_void),
+                )
+            });
+            result
         })
     }
 
@@ -230,10 +252,53 @@ impl Library {
     pub unsafe fn get<T>(&self, symbol: impl AsSymbolName) -> Result<Symbol<T>, crate::Error> {
         ensure_compatible_types::<T, FARPROC>()?;
         symbol.symbol_name(|windows_symbol| {
+            let result = traced!(
+                "GetProcAddress",
+                { library = self.0, symbol = ?crate::trace::Name(windows_symbol) },
+                with_get_last_error(
+                    |source| crate::Error::GetProcAddress { source },
+                    || {
+                        let symbol = GetProcAddress(self.0, windows_symbol.cast());
+                        if symbol.is_none() {
+                            None
+                        } else {
+                            Some(Symbol {
+                                pointer: symbol,
+                                pd: marker::PhantomData,
+                            })
+                        }
+                    },
+                )
+                .map_err(|e| e.unwrap_or(crate::Error::GetProcAddressUnknown))
+            );
+            observer::notify(|observer| {
+                observer.after_get(
+                    self.0 as *mut c_void,
+                    SymbolKey::Name(CStr::from_ptr(windows_symbol)),
+                    result.as_ref().map(|symbol| {
+                        symbol.pointer.map_or(ptr::null_mut(), |raw| raw as *mut c_void)
+                    }),
+                )
+            });
+            result
+        })
+    }
+
+    /// Get a pointer to a function or static variable by ordinal number.
+    ///
+    /// # Safety
+    ///
+    /// Users of this API must specify the correct type of the function or variable loaded.
+    pub unsafe fn get_ordinal<T>(&self, ordinal: u16) -> Result<Symbol<T>, crate::Error> {
+        ensure_compatible_types::<T, FARPROC>()?;
+        let result = traced!(
+            "GetProcAddress",
+            { library = self.0, ordinal },
             with_get_last_error(
                 |source| crate::Error::GetProcAddress { source },
                 || {
-                    let symbol = GetProcAddress(self.0, windows_symbol.cast());
+                    let ordinal = ordinal as usize as *const _;
+                    let symbol = GetProcAddress(self.0, ordinal);
                     if symbol.is_none() {
                         None
                     } else {
@@ -245,32 +310,17 @@ impl Library {
                 },
             )
             .map_err(|e| e.unwrap_or(crate::Error::GetProcAddressUnknown))
-        })
-    }
-
-    /// Get a pointer to a function or static variable by ordinal number.
-    ///
-    /// # Safety
-    ///
-    /// Users of this API must specify the correct type of the function or variable loaded.
-    pub unsafe fn get_ordinal<T>(&self, ordinal: u16) -> Result<Symbol<T>, crate::Error> {
-        ensure_compatible_types::<T, FARPROC>()?;
-        with_get_last_error(
-            |source| crate::Error::GetProcAddress { source },
-            || {
-                let ordinal = ordinal as usize as *const _;
-                let symbol = GetProcAddress(self.0, ordinal);
-                if symbol.is_none() {
-                    None
-                } else {
-                    Some(Symbol {
-                        pointer: symbol,
-                        pd: marker::PhantomData,
-                    })
-                }
-            },
-        )
-        .map_err(|e| e.unwrap_or(crate::Error::GetProcAddressUnknown))
+        );
+        observer::notify(|observer| {
+            observer.after_get(
+                self.0 as *mut c_void,
+                SymbolKey::Ordinal(ordinal),
+                result.as_ref().map(|symbol| {
+                    symbol.pointer.map_or(ptr::null_mut(), |raw| raw as *mut c_void)
+                }),
+            )
+        });
+        result
     }
 
     /// Convert the `Library` to a raw handle.
@@ -298,29 +348,67 @@ impl Library {
     ///
     /// The underlying data structures may still get leaked if an error does occur.
     pub fn close(self) -> Result<(), crate::Error> {
-        let result = with_get_last_error(
-            |source| crate::Error::FreeLibrary { source },
-            || {
-                if unsafe { FreeLibrary(self.0) == 0 } {
-                    None
-                } else {
-                    Some(())
-                }
-            },
-        )
-        .map_err(|e| e.unwrap_or(crate::Error::FreeLibraryUnknown));
+        let result = self.close_handle();
+        observer::notify(|observer| {
+            observer.after_close(self.0 as *mut c_void, result.as_ref().copied())
+        });
         // While the library is not free'd yet in case of an error, there is no reason to try
         // dropping it again, because all that will do is try calling `FreeLibrary` again. only
         // this time it would ignore the return result, which we already seen failing...
         mem::forget(self);
         result
     }
+
+    /// Call `FreeLibrary`, without consuming the `Library`.
+    fn close_handle(&self) -> Result<(), crate::Error> {
+        traced!(
+            "FreeLibrary",
+            { library = self.0 },
+            with_get_last_error(
+                |source| crate::Error::FreeLibrary { source },
+                || {
+                    if unsafe { FreeLibrary(self.0) == 0 } {
+                        None
+                    } else {
+                        Some(())
+                    }
+                },
+            )
+            .map_err(|e| e.unwrap_or(crate::Error::FreeLibraryUnknown))
+        )
+    }
+}
+
+impl crate::symbol_source::Sealed for Library {}
+impl crate::SymbolSource for Library {
+    type Symbol<'lib, T: 'lib> = Symbol<T>;
+    type Handle = HMODULE;
+
+    unsafe fn get<T>(&self, symbol: impl AsSymbolName) -> Result<Symbol<T>, crate::Error> {
+        Library::get(self, symbol)
+    }
+
+    /// The same as [`Library::get`], as `GetProcAddress` does not distinguish null symbols from
+    /// missing ones.
+    unsafe fn get_singlethreaded<T>(
+        &self,
+        symbol: impl AsSymbolName,
+    ) -> Result<Symbol<T>, crate::Error> {
+        Library::get(self, symbol)
+    }
+
+    fn handle(&self) -> HMODULE {
+        self.0
+    }
 }
 
 impl Drop for Library {
     fn drop(&mut self) {
-        unsafe {
-            FreeLibrary(self.0);
+        let result = self.close_handle();
+        if observer::is_observed() {
+            observer::notify(|observer| {
+                observer.after_drop(self.0 as *mut c_void, result.as_ref().copied())
+            });
         }
     }
 }
@@ -360,6 +448,15 @@ pub struct Symbol<T> {
 }
 
 impl<T> Symbol<T> {
+    /// A symbol with the address `pointer`, for [`StaticLibrary`](crate::StaticLibrary).
+    #[cfg(feature = "alloc")]
+    pub(crate) unsafe fn from_ptr(pointer: *mut core::ffi::c_void) -> Symbol<T> {
+        Symbol {
+            pointer: mem::transmute::<*mut core::ffi::c_void, FARPROC>(pointer),
+            pd: marker::PhantomData,
+        }
+    }
+
     /// Convert the loaded `Symbol` into a handle.
     pub fn into_raw(self) -> FARPROC {
         self.pointer
@@ -0,0 +1,274 @@
+//! Loading every plugin in a directory.
+//!
+//! See [`PluginManager`].
+
+use crate::manifest::AbiVersion;
+use crate::{Error, Library};
+use alloc::borrow::ToOwned;
+use alloc::ffi::CString;
+use alloc::vec::Vec;
+use core::ffi::c_int;
+use std::env::consts::{DLL_PREFIX, DLL_SUFFIX};
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+
+/// The signature of the initialisation function called after a plugin is loaded.
+///
+/// The function returns `0` on success. Any other value is reported as
+/// [`Error::PluginInit`].
+pub type PluginInit = unsafe extern "C" fn() -> c_int;
+
+/// The outcome of loading or closing a single plugin.
+pub type PluginResult = (PathBuf, Result<(), Error>);
+
+/// A plugin loaded by a [`PluginManager`].
+#[derive(Debug)]
+pub struct Plugin {
+    name: OsString,
+    path: PathBuf,
+    library: Library,
+}
+
+impl Plugin {
+    /// The name of the plugin: its filename without the platform's library prefix and suffix.
+    ///
+    /// [`library_filename`](crate::library_filename) of the name is the filename again.
+    pub fn name(&self) -> &OsStr {
+        &self.name
+    }
+
+    /// The path the plugin has been loaded from.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The loaded plugin.
+    pub fn library(&self) -> &Library {
+        &self.library
+    }
+}
+
+/// Loads the plugins in a directory and unloads them in the reverse order.
+///
+/// Every file in the directory named like [`library_filename`](crate::library_filename) would
+/// name a library (e.g. `libname.so` on Linux and `name.dll` on Windows) is a plugin. Plugins are
+/// loaded in the order of their filenames, so that the order does not depend on the file system.
+/// A plugin that fails to load does not prevent the others from being loaded.
+///
+/// After a plugin is loaded, its manifest is checked if [`PluginManager::require_manifest`] has
+/// been called, and its initialisation function of type [`PluginInit`] is called if
+/// [`PluginManager::init_symbol`] has been called.
+///
+/// The plugins are unloaded in the reverse order they have been loaded in, either explicitly
+/// with [`PluginManager::close`], which reports the outcome of every [`Library::close`], or when
+/// the manager is dropped.
+///
+/// # Examples
+///
+/// ```no_run
+/// use libloading::plugin::PluginManager;
+///
+/// let mut manager = PluginManager::new("/path/to/plugins").init_symbol("plugin_init");
+/// for (path, result) in unsafe { manager.load_all() }.unwrap() {
+///     if let Err(error) = result {
+///         eprintln!("could not load {}: {error}", path.display());
+///     }
+/// }
+/// for plugin in manager.plugins() {
+///     println!("loaded {:?}", plugin.name());
+/// }
+/// for (path, result) in manager.close() {
+///     if let Err(error) = result {
+///         eprintln!("could not unload {}: {error}", path.display());
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct PluginManager {
+    directory: PathBuf,
+    init_symbol: Option<CString>,
+    host_abi: Option<AbiVersion>,
+    plugins: Vec<Plugin>,
+}
+
+impl PluginManager {
+    /// Create a manager for the plugins in `directory`.
+    ///
+    /// No plugins are loaded until [`PluginManager::load_all`] or [`PluginManager::reload`] is
+    /// called.
+    pub fn new(directory: impl Into<PathBuf>) -> PluginManager {
+        PluginManager {
+            directory: directory.into(),
+            init_symbol: None,
+            host_abi: None,
+            plugins: Vec::new(),
+        }
+    }
+
+    /// Call the [`PluginInit`] function exported as `symbol` after loading each plugin.
+    ///
+    /// A plugin that does not export `symbol` fails to load.
+    ///
+    /// # Panics
+    ///
+    /// If `symbol` contains a null byte.
+    pub fn init_symbol(mut self, symbol: &str) -> PluginManager {
+        self.init_symbol = Some(CString::new(symbol).expect("symbol contains a null byte"));
+        self
+    }
+
+    /// Load plugins with [`Library::new_with_manifest`], requiring them to export a manifest
+    /// supported by `host_abi`.
+    pub fn require_manifest(mut self, host_abi: AbiVersion) -> PluginManager {
+        self.host_abi = Some(host_abi);
+        self
+    }
+
+    /// The directory the plugins are loaded from.
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// The loaded plugins, in the order they have been loaded in.
+    pub fn plugins(&self) -> &[Plugin] {
+        &self.plugins
+    }
+
+    /// The loaded plugin called `name`.
+    pub fn get(&self, name: impl AsRef<OsStr>) -> Option<&Plugin> {
+        self.plugins
+            .iter()
+            .find(|plugin| plugin.name == name.as_ref())
+    }
+
+    /// Load all plugins in the directory that have not been loaded yet.
+    ///
+    /// Returns the outcome for each plugin that has been attempted, in the order of their
+    /// filenames. An error is only returned if the directory cannot be read.
+    ///
+    /// # Safety
+    ///
+    /// See [`Library::new`]. Additionally, the initialisation functions must be of type
+    /// [`PluginInit`] and must be safe to call.
+    pub unsafe fn load_all(&mut self) -> Result<Vec<PluginResult>, Error> {
+        let mut candidates = Vec::new();
+        for entry in std::fs::read_dir(&self.directory).map_err(|source| Error::Io {
+            source: source.into(),
+        })? {
+            let entry = entry.map_err(|source| Error::Io {
+                source: source.into(),
+            })?;
+            if entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+                continue;
+            }
+            let path = entry.path();
+            if self.plugins.iter().any(|plugin| plugin.path == path) {
+                continue;
+            }
+            if let Some(name) = plugin_name(&entry.file_name()) {
+                candidates.push((name, path));
+            }
+        }
+        candidates.sort_by(|(_, a), (_, b)| a.cmp(b));
+        Ok(candidates
+            .into_iter()
+            .map(|(name, path)| {
+                let result = self.load(name, path.clone());
+                (path, result)
+            })
+            .collect())
+    }
+
+    /// Unload the plugin called `name` if it is loaded, and load it again.
+    ///
+    /// The plugin keeps its position in the unloading order. If it has not been loaded before,
+    /// it is loaded from the directory and unloaded before all the other plugins. If either
+    /// unloading or loading fails, the plugin is no longer loaded by the manager afterwards.
+    ///
+    /// # Safety
+    ///
+    /// See [`PluginManager::load_all`]. Additionally, nothing obtained from the plugin may be
+    /// used once it has been unloaded.
+    pub unsafe fn reload(&mut self, name: impl AsRef<OsStr>) -> Result<(), Error> {
+        let name = name.as_ref();
+        let position = self.plugins.iter().position(|plugin| plugin.name == name);
+        let path = match position {
+            Some(position) => {
+                let plugin = self.plugins.remove(position);
+                plugin.library.close()?;
+                plugin.path
+            }
+            None => self.directory.join(crate::library_filename(name)),
+        };
+        let library = self.open(&path)?;
+        let plugin = Plugin {
+            name: name.to_owned(),
+            path,
+            library,
+        };
+        match position {
+            Some(position) => self.plugins.insert(position, plugin),
+            None => self.plugins.push(plugin),
+        }
+        Ok(())
+    }
+
+    /// Unload all plugins in the reverse order they have been loaded in.
+    ///
+    /// Returns the outcome of [`Library::close`] for each plugin, in the order they have been
+    /// unloaded in. A failure to unload one plugin does not prevent the others from being
+    /// unloaded.
+    pub fn close(mut self) -> Vec<PluginResult> {
+        let mut results = Vec::with_capacity(self.plugins.len());
+        while let Some(plugin) = self.plugins.pop() {
+            results.push((plugin.path, plugin.library.close()));
+        }
+        results
+    }
+
+    unsafe fn load(&mut self, name: OsString, path: PathBuf) -> Result<(), Error> {
+        let library = self.open(&path)?;
+        self.plugins.push(Plugin {
+            name,
+            path,
+            library,
+        });
+        Ok(())
+    }
+
+    unsafe fn open(&self, path: &Path) -> Result<Library, Error> {
+        let library = match self.host_abi {
+            Some(host_abi) => Library::new_with_manifest(path, host_abi)?,
+            None => Library::new(path)?,
+        };
+        if let Some(symbol) = &self.init_symbol {
+            let init = library.get::<PluginInit>(&**symbol)?;
+            let status = init();
+            if status != 0 {
+                return Err(Error::PluginInit { status });
+            }
+        }
+        Ok(library)
+    }
+}
+
+impl Drop for PluginManager {
+    fn drop(&mut self) {
+        while let Some(plugin) = self.plugins.pop() {
+            drop(plugin.library);
+        }
+    }
+}
+
+/// The name of the plugin in the file called `filename`, if it is a library.
+fn plugin_name(filename: &OsStr) -> Option<OsString> {
+    let name = filename
+        .as_encoded_bytes()
+        .strip_prefix(DLL_PREFIX.as_bytes())?
+        .strip_suffix(DLL_SUFFIX.as_bytes())?;
+    if name.is_empty() {
+        return None;
+    }
+    // SAFE: `name` is `filename` with ASCII prefix and suffix removed.
+    Some(unsafe { OsStr::from_encoded_bytes_unchecked(name) }.to_owned())
+}
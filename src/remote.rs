@@ -0,0 +1,918 @@
+//! Loading libraries in a separate process.
+//!
+//! See [`RemoteLibrary`].
+
+use crate::as_filename::AsFilename;
+use crate::as_symbol_name::AsSymbolName;
+use crate::{Error, Library};
+use alloc::ffi::CString;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::ffi::{c_void, CStr};
+use core::mem;
+use std::ffi::OsStr;
+use std::io::{BufReader, PipeReader, Read, Write};
+use std::process::{Child, ChildStdin, Command, ExitStatus, Stdio};
+
+/// The maximum number of arguments of a function called with [`RemoteLibrary::call`].
+///
+/// Functions with more arguments cannot be called, since they may not all be passed in registers.
+pub const MAX_ARGUMENTS: usize = 4;
+
+/// An argument of a function called with [`RemoteLibrary::call`].
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Argument {
+    /// An `int32_t`.
+    I32(i32),
+    /// A `uint32_t`.
+    U32(u32),
+    /// An `int64_t`.
+    I64(i64),
+    /// A `uint64_t`.
+    U64(u64),
+    /// A `size_t` of the host process.
+    Usize(u64),
+    /// A `float`.
+    F32(f32),
+    /// A `double`.
+    F64(f64),
+    /// A pointer to a buffer.
+    ///
+    /// The buffer is copied into the host process before the call and copied back afterwards, so
+    /// that the function may modify it. Its length is not passed, pass it as a separate
+    /// [`Argument::Usize`] if the function needs it.
+    Bytes(Vec<u8>),
+}
+
+/// The return type of a function called with [`RemoteLibrary::call`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ReturnType {
+    /// `void`.
+    Void,
+    /// An `int32_t`.
+    I32,
+    /// A `uint32_t`.
+    U32,
+    /// An `int64_t`.
+    I64,
+    /// A `uint64_t`.
+    U64,
+    /// A `size_t` of the host process.
+    Usize,
+    /// A `float`.
+    F32,
+    /// A `double`.
+    F64,
+}
+
+/// The value returned by a function called with [`RemoteLibrary::call`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Value {
+    /// No value, the function returns `void`.
+    Void,
+    /// An `int32_t`.
+    I32(i32),
+    /// A `uint32_t`.
+    U32(u32),
+    /// An `int64_t`.
+    I64(i64),
+    /// A `uint64_t`.
+    U64(u64),
+    /// A `size_t` of the host process.
+    Usize(u64),
+    /// A `float`.
+    F32(f32),
+    /// A `double`.
+    F64(f64),
+}
+
+/// A symbol looked up with [`RemoteLibrary::get`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RemoteSymbol {
+    host: u32,
+    index: u32,
+}
+
+/// A dynamic library loaded into a separate host process.
+///
+/// The host process loads the library and calls its functions on behalf of this process, so that
+/// a library that crashes, e.g. with a segmentation fault, only terminates the host process. The
+/// error [`Error::HostExited`] is returned from then on.
+///
+/// Requests are sent to the host over its standard input and the responses are read from a
+/// separate pipe, so that the library is free to use the standard output, which is inherited
+/// along with the standard error.
+///
+/// The host program is any program that calls [`serve`]. This crate ships one as the
+/// `libloading-host` binary, but binaries are not built for the crates depending on this one, so
+/// it has to be installed separately, e.g. with `cargo install libloading --features remote`, and
+/// its path passed to [`RemoteLibrary::new`].
+///
+/// Only functions with the `"C"` ABI and up to [`MAX_ARGUMENTS`] arguments of the types
+/// described by [`Argument`] can be called, and only if the host program runs on x86_64 or
+/// AArch64. The library must have been built for the architecture of the host program, which need
+/// not be the architecture of this process.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use ::libloading::remote::{Argument, RemoteLibrary, ReturnType, Value};
+/// let mut library = RemoteLibrary::new("/path/to/libloading-host", "/path/to/awesome.module")?;
+/// let checksum = library.get("checksum")?;
+/// let mut arguments = [Argument::Bytes(b"data".to_vec()), Argument::Usize(4)];
+/// let value = library.call(checksum, &mut arguments, ReturnType::U32)?;
+/// assert!(matches!(value, Value::U32(_)));
+/// # Ok::<(), libloading::Error>(())
+/// ```
+pub struct RemoteLibrary {
+    host: Child,
+    requests: Option<ChildStdin>,
+    responses: BufReader<PipeReader>,
+    exited: Option<ExitStatus>,
+}
+
+impl RemoteLibrary {
+    /// Load a library into a new process running the host program at `host`, such as
+    /// `libloading-host`.
+    ///
+    /// Equivalent to [`RemoteLibrary::with_host`] with a [`Command`] running `host` without
+    /// arguments.
+    ///
+    /// Unlike [`Library::new`], this function is safe: the initialisation routines of the library
+    /// are executed in the host process, which cannot affect this one.
+    pub fn new(
+        host: impl AsRef<OsStr>,
+        filename: impl AsRef<OsStr>,
+    ) -> Result<RemoteLibrary, Error> {
+        RemoteLibrary::with_host(Command::new(host), filename)
+    }
+
+    /// Load a library into a new process running `host`.
+    ///
+    /// The `filename` is appended to the arguments of `host`, and its standard input is replaced
+    /// with a pipe. The host program must call [`serve`] with the `filename`.
+    ///
+    /// The errors that occur while loading the library in the host are returned like they are
+    /// returned by [`Library::new`].
+    pub fn with_host(
+        mut host: Command,
+        filename: impl AsRef<OsStr>,
+    ) -> Result<RemoteLibrary, Error> {
+        let (responses, writer) = std::io::pipe().map_err(io_error)?;
+        #[cfg(unix)]
+        let writer = unix::pass_to_child(&mut host, writer)?;
+        let mut child = host
+            .arg(filename)
+            .stdin(Stdio::piped())
+            .spawn()
+            .map_err(io_error)?;
+        // The `Command` keeps the writing end open on Unix, and the end of the responses is only
+        // seen once the host holds the last one.
+        drop(host);
+        let requests = child.stdin.take();
+        let mut library = RemoteLibrary {
+            host: child,
+            requests,
+            responses: BufReader::new(responses),
+            exited: None,
+        };
+        #[cfg(windows)]
+        let writer = windows::pass_to_child(&library.host, writer)?;
+        // The host learns where to send the responses from the first request.
+        library.send(&writer.to_le_bytes())?;
+        library.receive()?;
+        Ok(library)
+    }
+
+    /// The process identifier of the host process.
+    pub fn host_id(&self) -> u32 {
+        self.host.id()
+    }
+
+    /// Look up a symbol in the library.
+    ///
+    /// The errors are returned like they are returned by [`Library::get`].
+    pub fn get(&mut self, symbol: impl AsSymbolName) -> Result<RemoteSymbol, Error> {
+        let mut request = alloc::vec![REQUEST_GET];
+        symbol.symbol_name(|posix_symbol| unsafe {
+            // SAFE: `posix_symbol` is a null-terminated string.
+            request.extend_from_slice(CStr::from_ptr(posix_symbol).to_bytes());
+            Ok(())
+        })?;
+        let response = self.request(&request)?;
+        let index = Decoder(&response).u32()?;
+        Ok(RemoteSymbol {
+            host: self.host.id(),
+            index,
+        })
+    }
+
+    /// Call the function `symbol` with `arguments`.
+    ///
+    /// The function must have the `"C"` ABI, and the signature described by the types of the
+    /// `arguments` and `returns`. If it does not, the behaviour in the host process is undefined,
+    /// which usually means that it crashes or returns a wrong value. The buffers of the
+    /// [`Argument::Bytes`] arguments are replaced with their contents after the call.
+    ///
+    /// At most [`MAX_ARGUMENTS`], i.e. 4, arguments can be passed, and functions can only be
+    /// called if the host program runs on x86_64 or AArch64. Otherwise, as well as when calling a
+    /// function of a different `RemoteLibrary`, an [`Error::Remote`] is returned.
+    pub fn call(
+        &mut self,
+        symbol: RemoteSymbol,
+        arguments: &mut [Argument],
+        returns: ReturnType,
+    ) -> Result<Value, Error> {
+        if symbol.host != self.host.id() {
+            return Err(remote_error("symbol belongs to a different host process"));
+        }
+        let mut request = alloc::vec![REQUEST_CALL];
+        request.extend_from_slice(&symbol.index.to_le_bytes());
+        request.push(return_tag(returns));
+        request.push(u8::try_from(arguments.len()).unwrap_or(u8::MAX));
+        for argument in arguments.iter() {
+            encode_argument(argument, &mut request);
+        }
+        let response = self.request(&request)?;
+        let mut decoder = Decoder(&response);
+        let value = decode_value(&mut decoder)?;
+        for argument in arguments.iter_mut() {
+            if let Argument::Bytes(buffer) = argument {
+                *buffer = decoder.bytes()?.to_vec();
+            }
+        }
+        Ok(value)
+    }
+
+    /// Unload the library and wait for the host process to exit.
+    ///
+    /// Returns [`Error::HostExited`] if the host process did not exit successfully.
+    pub fn close(mut self) -> Result<(), Error> {
+        if let Some(status) = self.exited {
+            return Err(Error::HostExited { status });
+        }
+        // Closing the standard input of the host asks it to exit.
+        self.requests = None;
+        let status = self.host.wait().map_err(io_error)?;
+        self.exited = Some(status);
+        if status.success() {
+            Ok(())
+        } else {
+            Err(Error::HostExited { status })
+        }
+    }
+
+    /// Send a request and receive the response to it.
+    fn request(&mut self, request: &[u8]) -> Result<Vec<u8>, Error> {
+        self.send(request)?;
+        self.receive()
+    }
+
+    /// Send a request.
+    fn send(&mut self, request: &[u8]) -> Result<(), Error> {
+        if let Some(status) = self.exited {
+            return Err(Error::HostExited { status });
+        }
+        let requests = self.requests.as_mut().expect("only closed by `close`");
+        if write_message(requests, request).is_err() {
+            return Err(self.host_exited());
+        }
+        Ok(())
+    }
+
+    /// Receive a response, and decode the error if it reports one.
+    fn receive(&mut self) -> Result<Vec<u8>, Error> {
+        let response = match read_message(&mut self.responses) {
+            Ok(Some(response)) => response,
+            Ok(None) | Err(_) => return Err(self.host_exited()),
+        };
+        match response.split_first() {
+            Some((&RESPONSE_OK, rest)) => Ok(rest.to_vec()),
+            Some((&RESPONSE_ERROR, rest)) => Err(decode_error(&mut Decoder(rest))?),
+            _ => Err(malformed()),
+        }
+    }
+
+    /// Wait for the host process that stopped responding to exit.
+    fn host_exited(&mut self) -> Error {
+        match self.host.wait() {
+            Ok(status) => {
+                self.exited = Some(status);
+                Error::HostExited { status }
+            }
+            Err(error) => io_error(error),
+        }
+    }
+}
+
+impl Drop for RemoteLibrary {
+    fn drop(&mut self) {
+        if self.exited.is_none() {
+            // The host may be stuck in a call, so it is not asked to exit.
+            let _ = self.host.kill();
+            let _ = self.host.wait();
+        }
+    }
+}
+
+impl core::fmt::Debug for RemoteLibrary {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("RemoteLibrary")
+            .field("host_id", &self.host.id())
+            .field("exited", &self.exited)
+            .finish()
+    }
+}
+
+/// Serve the requests of a [`RemoteLibrary`] for the library `filename`.
+///
+/// The requests are read from the standard input until it is closed. The responses are written to
+/// the pipe the [`RemoteLibrary`] names in its first request.
+///
+/// This is the implementation of the `libloading-host` binary. It returns the error loading the
+/// library failed with, or an [`Error::Io`] if reading the requests or writing the responses
+/// failed.
+///
+/// # Safety
+///
+/// See [`Library::new`]. Additionally, the functions called by the [`RemoteLibrary`] must have
+/// the signatures it describes.
+pub unsafe fn serve(filename: impl AsFilename) -> Result<(), Error> {
+    let mut requests = std::io::stdin().lock();
+    let responses = read_message(&mut requests)
+        .map_err(io_error)?
+        .ok_or_else(malformed)?;
+    let responses = u64::from_le_bytes(Decoder(&responses).array()?);
+    #[cfg(unix)]
+    let mut responses = unix::responses(responses)?;
+    #[cfg(windows)]
+    let mut responses = windows::responses(responses)?;
+    let library = match Library::new(filename) {
+        Ok(library) => library,
+        Err(error) => {
+            let mut response = alloc::vec![RESPONSE_ERROR];
+            encode_error(&error, &mut response);
+            write_message(&mut responses, &response).map_err(io_error)?;
+            return Err(error);
+        }
+    };
+    write_message(&mut responses, &[RESPONSE_OK]).map_err(io_error)?;
+    let mut symbols = Vec::new();
+    while let Some(request) = read_message(&mut requests).map_err(io_error)? {
+        let response = match handle(&library, &mut symbols, &request) {
+            Ok(mut response) => {
+                response.insert(0, RESPONSE_OK);
+                response
+            }
+            Err(error) => {
+                let mut response = alloc::vec![RESPONSE_ERROR];
+                encode_error(&error, &mut response);
+                response
+            }
+        };
+        write_message(&mut responses, &response).map_err(io_error)?;
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+mod unix {
+    use super::{io_error, malformed};
+    use crate::Error;
+    use core::ffi::c_int;
+    use std::io::PipeWriter;
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+    use std::os::unix::process::CommandExt;
+    use std::process::Command;
+
+    extern "C" {
+        fn dup2(old: c_int, new: c_int) -> c_int;
+    }
+
+    /// Make `writer` available to the process spawned by `host`, and return its descriptor there.
+    pub(super) fn pass_to_child(host: &mut Command, writer: PipeWriter) -> Result<u64, Error> {
+        // Descriptors are closed when the host program is executed, except for the copy of
+        // `writer` that replaces `target`. No other descriptor can have the number of `target`
+        // in the child, as it is open in this process until the child has been spawned.
+        let target = writer.try_clone().map_err(io_error)?;
+        let number = target.as_raw_fd();
+        unsafe {
+            host.pre_exec(move || {
+                if dup2(writer.as_raw_fd(), target.as_raw_fd()) < 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+        Ok(number as u64)
+    }
+
+    /// Take ownership of the descriptor named by the [`RemoteLibrary`](super::RemoteLibrary).
+    pub(super) unsafe fn responses(descriptor: u64) -> Result<std::fs::File, Error> {
+        let descriptor = c_int::try_from(descriptor).map_err(|_| malformed())?;
+        Ok(OwnedFd::from_raw_fd(descriptor).into())
+    }
+}
+
+#[cfg(windows)]
+mod windows {
+    use super::{io_error, malformed};
+    use crate::Error;
+    use std::io::PipeWriter;
+    use std::os::windows::io::{AsRawHandle, FromRawHandle, OwnedHandle, RawHandle};
+    use std::process::Child;
+
+    windows_link::link!("kernel32.dll" "system" fn GetCurrentProcess() -> isize);
+    windows_link::link!("kernel32.dll" "system" fn DuplicateHandle(source_process: isize, source: isize, target_process: isize, target: *mut isize, access: u32, inherit: i32, options: u32) -> i32);
+
+    const DUPLICATE_SAME_ACCESS: u32 = 2;
+
+    /// Duplicate `writer` into `child`, and return its handle there.
+    ///
+    /// The handle is not inherited, so that no other process spawned in the meantime can keep
+    /// the pipe open.
+    pub(super) fn pass_to_child(child: &Child, writer: PipeWriter) -> Result<u64, Error> {
+        let mut target = 0;
+        let duplicated = unsafe {
+            DuplicateHandle(
+                GetCurrentProcess(),
+                writer.as_raw_handle() as isize,
+                child.as_raw_handle() as isize,
+                &mut target,
+                0,
+                0,
+                DUPLICATE_SAME_ACCESS,
+            )
+        };
+        if duplicated == 0 {
+            return Err(io_error(std::io::Error::last_os_error()));
+        }
+        Ok(target as u64)
+    }
+
+    /// Take ownership of the handle named by the [`RemoteLibrary`](super::RemoteLibrary).
+    pub(super) unsafe fn responses(handle: u64) -> Result<std::fs::File, Error> {
+        let handle = usize::try_from(handle).map_err(|_| malformed())?;
+        Ok(OwnedHandle::from_raw_handle(handle as RawHandle).into())
+    }
+}
+
+/// Handle a request in the host process.
+unsafe fn handle(
+    library: &Library,
+    symbols: &mut Vec<*mut c_void>,
+    request: &[u8],
+) -> Result<Vec<u8>, Error> {
+    let mut decoder = Decoder(request);
+    match decoder.u8()? {
+        REQUEST_GET => {
+            let symbol = library.get::<*mut c_void>(decoder.0)?;
+            let index = u32::try_from(symbols.len()).map_err(|_| malformed())?;
+            symbols.push(*symbol);
+            Ok(index.to_le_bytes().to_vec())
+        }
+        REQUEST_CALL => {
+            let function = *symbols.get(decoder.u32()? as usize).ok_or_else(malformed)?;
+            if function.is_null() {
+                return Err(remote_error("symbol is null"));
+            }
+            let returns = decode_return_type(decoder.u8()?)?;
+            let count = usize::from(decoder.u8()?);
+            if count > MAX_ARGUMENTS {
+                return Err(remote_error(&alloc::format!(
+                    "{count} arguments given, but at most {MAX_ARGUMENTS} are supported"
+                )));
+            }
+            let mut arguments = Vec::with_capacity(count);
+            for _ in 0..count {
+                arguments.push(decode_argument(&mut decoder)?);
+            }
+            let registers = arguments.iter_mut().map(Register::new).collect::<Vec<_>>();
+            let mut response = Vec::new();
+            encode_value(&call(function, returns, &registers)?, &mut response);
+            for argument in &arguments {
+                if let Argument::Bytes(buffer) = argument {
+                    encode_bytes(buffer, &mut response);
+                }
+            }
+            Ok(response)
+        }
+        _ => Err(malformed()),
+    }
+}
+
+/// An argument as it is passed to the function, in an integer or a floating point register.
+#[derive(Clone, Copy)]
+#[cfg_attr(
+    not(any(target_arch = "x86_64", target_arch = "aarch64")),
+    allow(dead_code)
+)]
+enum Register {
+    Integer(u64),
+    Float(f64),
+}
+
+impl Register {
+    fn new(argument: &mut Argument) -> Register {
+        match *argument {
+            // Only the low bits of the register are read, but they are extended like C does.
+            Argument::I32(value) => Register::Integer(value as i64 as u64),
+            Argument::U32(value) => Register::Integer(u64::from(value)),
+            Argument::I64(value) => Register::Integer(value as u64),
+            Argument::U64(value) | Argument::Usize(value) => Register::Integer(value),
+            // A `float` is passed in the low bits of the register.
+            Argument::F32(value) => Register::Float(f64::from_bits(u64::from(value.to_bits()))),
+            Argument::F64(value) => Register::Float(value),
+            Argument::Bytes(ref mut buffer) => Register::Integer(buffer.as_mut_ptr() as u64),
+        }
+    }
+}
+
+/// Call `function` with `registers` as the arguments.
+///
+/// Every argument is passed in a whole register, and every value is returned in one, so that only
+/// the classes of the arguments and of the return value determine the type the function is
+/// called as. This holds for the C calling conventions of x86_64 and AArch64, as long as the
+/// arguments fit into registers, which [`MAX_ARGUMENTS`] ensures.
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+unsafe fn call(
+    function: *mut c_void,
+    returns: ReturnType,
+    registers: &[Register],
+) -> Result<Value, Error> {
+    let integer = || call_registers::<u64>(function, registers);
+    let float = || call_registers::<f64>(function, registers);
+    Ok(match returns {
+        ReturnType::Void => {
+            call_registers::<()>(function, registers);
+            Value::Void
+        }
+        ReturnType::I32 => Value::I32(integer() as i32),
+        ReturnType::U32 => Value::U32(integer() as u32),
+        ReturnType::I64 => Value::I64(integer() as i64),
+        ReturnType::U64 => Value::U64(integer()),
+        ReturnType::Usize => Value::Usize(integer()),
+        ReturnType::F32 => Value::F32(f32::from_bits(float().to_bits() as u32)),
+        ReturnType::F64 => Value::F64(float()),
+    })
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+unsafe fn call(_: *mut c_void, _: ReturnType, _: &[Register]) -> Result<Value, Error> {
+    Err(remote_error(
+        "calling functions is only supported on x86_64 and AArch64",
+    ))
+}
+
+/// Call `function` with `registers`, returning the value in the register for `R`.
+///
+/// The function is called as the type with exactly one parameter for each register, of its class.
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+unsafe fn call_registers<R>(function: *mut c_void, registers: &[Register]) -> R {
+    macro_rules! class {
+        (Integer) => {
+            u64
+        };
+        (Float) => {
+            f64
+        };
+    }
+    macro_rules! call_as {
+        ($([$($class:ident $argument:ident),*]),*) => {
+            match *registers {
+                $([$(Register::$class($argument)),*] => {
+                    mem::transmute::<*mut c_void, extern "C" fn($(class!($class)),*) -> R>(
+                        function,
+                    )($($argument),*)
+                })*
+                _ => unreachable!("more than MAX_ARGUMENTS arguments"),
+            }
+        };
+    }
+    call_as!(
+        [],
+        [Integer a],
+        [Float a],
+        [Integer a, Integer b],
+        [Integer a, Float b],
+        [Float a, Integer b],
+        [Float a, Float b],
+        [Integer a, Integer b, Integer c],
+        [Integer a, Integer b, Float c],
+        [Integer a, Float b, Integer c],
+        [Integer a, Float b, Float c],
+        [Float a, Integer b, Integer c],
+        [Float a, Integer b, Float c],
+        [Float a, Float b, Integer c],
+        [Float a, Float b, Float c],
+        [Integer a, Integer b, Integer c, Integer d],
+        [Integer a, Integer b, Integer c, Float d],
+        [Integer a, Integer b, Float c, Integer d],
+        [Integer a, Integer b, Float c, Float d],
+        [Integer a, Float b, Integer c, Integer d],
+        [Integer a, Float b, Integer c, Float d],
+        [Integer a, Float b, Float c, Integer d],
+        [Integer a, Float b, Float c, Float d],
+        [Float a, Integer b, Integer c, Integer d],
+        [Float a, Integer b, Integer c, Float d],
+        [Float a, Integer b, Float c, Integer d],
+        [Float a, Integer b, Float c, Float d],
+        [Float a, Float b, Integer c, Integer d],
+        [Float a, Float b, Integer c, Float d],
+        [Float a, Float b, Float c, Integer d],
+        [Float a, Float b, Float c, Float d]
+    )
+}
+
+const REQUEST_GET: u8 = 0;
+const REQUEST_CALL: u8 = 1;
+const RESPONSE_OK: u8 = 0;
+const RESPONSE_ERROR: u8 = 1;
+
+/// Write a message, prefixed with its length.
+fn write_message(output: &mut impl Write, message: &[u8]) -> std::io::Result<()> {
+    let length = u32::try_from(message.len()).map_err(std::io::Error::other)?;
+    output.write_all(&length.to_le_bytes())?;
+    output.write_all(message)?;
+    output.flush()
+}
+
+/// Read a message, or `None` if the other end has been closed.
+fn read_message(input: &mut impl Read) -> std::io::Result<Option<Vec<u8>>> {
+    let mut length = [0; 4];
+    match input.read_exact(&mut length) {
+        Ok(()) => {}
+        Err(error) if error.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(error) => return Err(error),
+    }
+    let length = u32::from_le_bytes(length);
+    let mut message = Vec::new();
+    input.take(u64::from(length)).read_to_end(&mut message)?;
+    if message.len() != length as usize {
+        return Err(std::io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(Some(message))
+}
+
+fn encode_bytes(bytes: &[u8], output: &mut Vec<u8>) {
+    output.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+    output.extend_from_slice(bytes);
+}
+
+fn encode_argument(argument: &Argument, output: &mut Vec<u8>) {
+    match *argument {
+        Argument::I32(value) => {
+            output.push(0);
+            output.extend_from_slice(&value.to_le_bytes());
+        }
+        Argument::U32(value) => {
+            output.push(1);
+            output.extend_from_slice(&value.to_le_bytes());
+        }
+        Argument::I64(value) => {
+            output.push(2);
+            output.extend_from_slice(&value.to_le_bytes());
+        }
+        Argument::U64(value) => {
+            output.push(3);
+            output.extend_from_slice(&value.to_le_bytes());
+        }
+        Argument::Usize(value) => {
+            output.push(4);
+            output.extend_from_slice(&value.to_le_bytes());
+        }
+        Argument::F32(value) => {
+            output.push(5);
+            output.extend_from_slice(&value.to_le_bytes());
+        }
+        Argument::F64(value) => {
+            output.push(6);
+            output.extend_from_slice(&value.to_le_bytes());
+        }
+        Argument::Bytes(ref buffer) => {
+            output.push(7);
+            encode_bytes(buffer, output);
+        }
+    }
+}
+
+fn decode_argument(decoder: &mut Decoder<'_>) -> Result<Argument, Error> {
+    Ok(match decoder.u8()? {
+        0 => Argument::I32(i32::from_le_bytes(decoder.array()?)),
+        1 => Argument::U32(u32::from_le_bytes(decoder.array()?)),
+        2 => Argument::I64(i64::from_le_bytes(decoder.array()?)),
+        3 => Argument::U64(u64::from_le_bytes(decoder.array()?)),
+        4 => Argument::Usize(u64::from_le_bytes(decoder.array()?)),
+        5 => Argument::F32(f32::from_le_bytes(decoder.array()?)),
+        6 => Argument::F64(f64::from_le_bytes(decoder.array()?)),
+        7 => Argument::Bytes(decoder.bytes()?.to_vec()),
+        _ => return Err(malformed()),
+    })
+}
+
+fn return_tag(returns: ReturnType) -> u8 {
+    match returns {
+        ReturnType::Void => 0,
+        ReturnType::I32 => 1,
+        ReturnType::U32 => 2,
+        ReturnType::I64 => 3,
+        ReturnType::U64 => 4,
+        ReturnType::Usize => 5,
+        ReturnType::F32 => 6,
+        ReturnType::F64 => 7,
+    }
+}
+
+fn decode_return_type(tag: u8) -> Result<ReturnType, Error> {
+    Ok(match tag {
+        0 => ReturnType::Void,
+        1 => ReturnType::I32,
+        2 => ReturnType::U32,
+        3 => ReturnType::I64,
+        4 => ReturnType::U64,
+        5 => ReturnType::Usize,
+        6 => ReturnType::F32,
+        7 => ReturnType::F64,
+        _ => return Err(malformed()),
+    })
+}
+
+fn encode_value(value: &Value, output: &mut Vec<u8>) {
+    match *value {
+        Value::Void => output.push(0),
+        Value::I32(value) => {
+            output.push(1);
+            output.extend_from_slice(&value.to_le_bytes());
+        }
+        Value::U32(value) => {
+            output.push(2);
+            output.extend_from_slice(&value.to_le_bytes());
+        }
+        Value::I64(value) => {
+            output.push(3);
+            output.extend_from_slice(&value.to_le_bytes());
+        }
+        Value::U64(value) => {
+            output.push(4);
+            output.extend_from_slice(&value.to_le_bytes());
+        }
+        Value::Usize(value) => {
+            output.push(5);
+            output.extend_from_slice(&value.to_le_bytes());
+        }
+        Value::F32(value) => {
+            output.push(6);
+            output.extend_from_slice(&value.to_le_bytes());
+        }
+        Value::F64(value) => {
+            output.push(7);
+            output.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+}
+
+fn decode_value(decoder: &mut Decoder<'_>) -> Result<Value, Error> {
+    Ok(match decoder.u8()? {
+        0 => Value::Void,
+        1 => Value::I32(i32::from_le_bytes(decoder.array()?)),
+        2 => Value::U32(u32::from_le_bytes(decoder.array()?)),
+        3 => Value::I64(i64::from_le_bytes(decoder.array()?)),
+        4 => Value::U64(u64::from_le_bytes(decoder.array()?)),
+        5 => Value::Usize(u64::from_le_bytes(decoder.array()?)),
+        6 => Value::F32(f32::from_le_bytes(decoder.array()?)),
+        7 => Value::F64(f64::from_le_bytes(decoder.array()?)),
+        _ => return Err(malformed()),
+    })
+}
+
+// The errors of loading a library and looking up symbols are sent as they are, everything else
+// is sent as its message.
+const ERROR_DL_OPEN: u8 = 0;
+const ERROR_DL_OPEN_UNKNOWN: u8 = 1;
+const ERROR_DL_SYM: u8 = 2;
+const ERROR_DL_SYM_UNKNOWN: u8 = 3;
+const ERROR_LOAD_LIBRARY_EX_W: u8 = 4;
+const ERROR_LOAD_LIBRARY_EX_W_UNKNOWN: u8 = 5;
+const ERROR_GET_PROC_ADDRESS: u8 = 6;
+const ERROR_GET_PROC_ADDRESS_UNKNOWN: u8 = 7;
+const ERROR_INTERIOR_ZERO_ELEMENTS: u8 = 8;
+const ERROR_OTHER: u8 = 255;
+
+fn encode_error(error: &Error, output: &mut Vec<u8>) {
+    match error {
+        Error::DlOpen { source } => {
+            output.push(ERROR_DL_OPEN);
+            output.extend_from_slice(source.0.as_bytes());
+        }
+        Error::DlOpenUnknown => output.push(ERROR_DL_OPEN_UNKNOWN),
+        Error::DlSym { source } => {
+            output.push(ERROR_DL_SYM);
+            output.extend_from_slice(source.0.as_bytes());
+        }
+        Error::DlSymUnknown => output.push(ERROR_DL_SYM_UNKNOWN),
+        Error::LoadLibraryExW { source } => {
+            output.push(ERROR_LOAD_LIBRARY_EX_W);
+            output.extend_from_slice(&source.0.to_le_bytes());
+        }
+        Error::LoadLibraryExWUnknown => output.push(ERROR_LOAD_LIBRARY_EX_W_UNKNOWN),
+        Error::GetProcAddress { source } => {
+            output.push(ERROR_GET_PROC_ADDRESS);
+            output.extend_from_slice(&source.0.to_le_bytes());
+        }
+        Error::GetProcAddressUnknown => output.push(ERROR_GET_PROC_ADDRESS_UNKNOWN),
+        Error::InteriorZeroElements => output.push(ERROR_INTERIOR_ZERO_ELEMENTS),
+        Error::Remote { message } => {
+            output.push(ERROR_OTHER);
+            output.extend_from_slice(message.as_bytes());
+        }
+        error => {
+            output.push(ERROR_OTHER);
+            let mut message = error.to_string();
+            let mut source = core::error::Error::source(error);
+            while let Some(error) = source {
+                message = alloc::format!("{message}: {error}");
+                source = error.source();
+            }
+            output.extend_from_slice(message.as_bytes());
+        }
+    }
+}
+
+fn decode_error(decoder: &mut Decoder<'_>) -> Result<Error, Error> {
+    let dl_error = |message: &[u8]| {
+        CString::new(message)
+            .map(crate::error::DlError)
+            .map_err(|_| malformed())
+    };
+    Ok(match decoder.u8()? {
+        ERROR_DL_OPEN => Error::DlOpen {
+            source: dl_error(decoder.0)?,
+        },
+        ERROR_DL_OPEN_UNKNOWN => Error::DlOpenUnknown,
+        ERROR_DL_SYM => Error::DlSym {
+            source: dl_error(decoder.0)?,
+        },
+        ERROR_DL_SYM_UNKNOWN => Error::DlSymUnknown,
+        ERROR_LOAD_LIBRARY_EX_W => Error::LoadLibraryExW {
+            source: crate::error::WindowsError(i32::from_le_bytes(decoder.array()?)),
+        },
+        ERROR_LOAD_LIBRARY_EX_W_UNKNOWN => Error::LoadLibraryExWUnknown,
+        ERROR_GET_PROC_ADDRESS => Error::GetProcAddress {
+            source: crate::error::WindowsError(i32::from_le_bytes(decoder.array()?)),
+        },
+        ERROR_GET_PROC_ADDRESS_UNKNOWN => Error::GetProcAddressUnknown,
+        ERROR_INTERIOR_ZERO_ELEMENTS => Error::InteriorZeroElements,
+        ERROR_OTHER => Error::Remote {
+            message: String::from_utf8_lossy(decoder.0).into_owned(),
+        },
+        _ => return Err(malformed()),
+    })
+}
+
+/// Reads the fields of a message.
+struct Decoder<'a>(&'a [u8]);
+
+impl<'a> Decoder<'a> {
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        let (array, rest) = self.0.split_first_chunk().ok_or_else(malformed)?;
+        self.0 = rest;
+        Ok(*array)
+    }
+
+    fn u8(&mut self) -> Result<u8, Error> {
+        self.array().map(u8::from_le_bytes)
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        self.array().map(u32::from_le_bytes)
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8], Error> {
+        let length = self.u32()? as usize;
+        if self.0.len() < length {
+            return Err(malformed());
+        }
+        let (bytes, rest) = self.0.split_at(length);
+        self.0 = rest;
+        Ok(bytes)
+    }
+}
+
+fn malformed() -> Error {
+    remote_error("malformed message")
+}
+
+fn remote_error(message: &str) -> Error {
+    Error::Remote {
+        message: message.into(),
+    }
+}
+
+fn io_error(source: std::io::Error) -> Error {
+    Error::Io {
+        source: source.into(),
+    }
+}
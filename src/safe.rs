@@ -7,9 +7,19 @@ use super::os::windows as imp;
 use super::Error;
 use crate::as_filename::AsFilename;
 use crate::as_symbol_name::AsSymbolName;
+use crate::data::{check_data_symbol, slice_symbol, AtomicData, Static};
+use crate::manifest::{AbiVersion, ManifestError, PluginManifest};
+use crate::symbol_source::SymbolSource;
+use crate::symbol_type::SymbolType;
+#[cfg(feature = "std")]
+use crate::unwind::UnwindFn;
 use core::fmt;
 use core::marker;
 use core::ops;
+#[cfg(feature = "std")]
+use alloc::sync::Arc;
+#[cfg(feature = "std")]
+use core::future::Future;
 
 /// A loaded dynamic library.
 #[cfg_attr(libloading_docs, doc(cfg(any(unix, windows))))]
@@ -85,6 +95,139 @@ impl Library {
         imp::Library::new(filename).map(From::from)
     }
 
+    /// Find and load a dynamic library without blocking the calling thread.
+    ///
+    /// This is [`Library::new`] running on another thread, which is useful in asynchronous code:
+    /// loading a large library may block for a long time while its initialisation routines run.
+    /// The returned future completes once the library has been loaded, and can be awaited with
+    /// any executor. With the `tokio` feature, the library is loaded on the blocking thread pool
+    /// of the current Tokio runtime, if there is one; otherwise a new thread is spawned.
+    ///
+    /// A panic while loading the library is resumed when the future is polled.
+    ///
+    /// # Safety
+    ///
+    /// See [`Library::new`]. The initialisation routines run on a different thread, which some
+    /// libraries may not expect.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use ::libloading::Library;
+    /// # async fn load() -> Result<(), libloading::Error> {
+    /// let library = unsafe { Library::new_async("/path/to/awesome.module") }.await?;
+    /// # Ok(()) }
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(libloading_docs, doc(cfg(feature = "std")))]
+    pub unsafe fn new_async(
+        filename: impl AsFilename + Send + 'static,
+    ) -> impl Future<Output = Result<Library, Error>> + Send + 'static {
+        crate::blocking::spawn(move || Library::new(filename))
+    }
+
+    /// Check whether the library file at `filename` can be loaded into the running process.
+    ///
+    /// The file is only read; no initialisation routines are executed. See
+    /// [`os::unix::Library::check_compatible`] for details on what is checked.
+    ///
+    /// [`os::unix::Library::check_compatible`]: crate::os::unix::Library::check_compatible
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use ::libloading::Library;
+    /// let report = Library::check_compatible("/path/to/awesome.module").unwrap();
+    /// for incompatibility in &report.incompatibilities {
+    ///     eprintln!("cannot load awesome.module: {incompatibility}");
+    /// }
+    /// ```
+    #[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+    #[cfg_attr(
+        libloading_docs,
+        doc(cfg(all(feature = "std", any(target_os = "linux", target_os = "android"))))
+    )]
+    pub fn check_compatible(
+        filename: impl AsFilename,
+    ) -> Result<imp::CompatibilityReport, Error> {
+        imp::Library::check_compatible(filename)
+    }
+
+    /// Find and load a dynamic library after checking that it is compatible with the running
+    /// process.
+    ///
+    /// This is equivalent to [`Library::new`], except that [`Library::check_compatible`] is
+    /// consulted first. If any incompatibilities are found, [`Error::Incompatible`] describing
+    /// them is returned instead of the less descriptive error reported by the system loader.
+    ///
+    /// # Safety
+    ///
+    /// See [`Library::new`].
+    #[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+    #[cfg_attr(
+        libloading_docs,
+        doc(cfg(all(feature = "std", any(target_os = "linux", target_os = "android"))))
+    )]
+    pub unsafe fn new_checked(filename: impl AsFilename) -> Result<Library, Error> {
+        imp::Library::open_checked(filename, imp::RTLD_LAZY | imp::RTLD_LOCAL).map(From::from)
+    }
+
+    /// Find and load a plugin after checking its [`PluginManifest`].
+    ///
+    /// The library is loaded as with [`Library::new`] and the manifest exported by
+    /// [`plugin_manifest!`](crate::plugin_manifest) is checked against `host_abi` with
+    /// [`PluginManifest::check`] before any other symbol of the library is used. If the manifest
+    /// is rejected, the library is unloaded and [`Error::Manifest`] is returned. If the library
+    /// does not export a manifest, the error from looking up [`PluginManifest::SYMBOL`] is
+    /// returned.
+    ///
+    /// # Safety
+    ///
+    /// See [`Library::new`]. Additionally, if the library exports [`PluginManifest::SYMBOL`], it
+    /// must be a [`PluginManifest`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use ::libloading::Library;
+    /// use libloading::manifest::AbiVersion;
+    ///
+    /// let plugin = unsafe {
+    ///     Library::new_with_manifest("/path/to/plugin.module", AbiVersion::new(1, 3, 0))
+    /// };
+    /// match plugin {
+    ///     Ok(plugin) => { /* use the plugin */ }
+    ///     Err(libloading::Error::Manifest { source }) => eprintln!("rejected: {source}"),
+    ///     Err(error) => eprintln!("could not load: {error}"),
+    /// }
+    /// ```
+    pub unsafe fn new_with_manifest(
+        filename: impl AsFilename,
+        host_abi: AbiVersion,
+    ) -> Result<Library, Error> {
+        let library = Library::new(filename)?;
+        let manifest = *library.get::<*const PluginManifest>(PluginManifest::SYMBOL)?;
+        // The symbol may be too small to be a manifest, so it is only read in full once it has
+        // been identified as one by its size, where known, and its magic.
+        if let Some(found) = crate::data::symbol_size(manifest.cast()) {
+            let expected = core::mem::size_of::<PluginManifest>();
+            if found < expected {
+                return Err(Error::SymbolSize { found, expected });
+            }
+        }
+        let magic = manifest.cast::<u64>().read_unaligned();
+        if magic != PluginManifest::MAGIC {
+            return Err(Error::Manifest {
+                source: ManifestError::BadMagic { found: magic },
+            });
+        }
+        manifest
+            .read_unaligned()
+            .check(host_abi)
+            .map_err(|source| Error::Manifest { source })?;
+        Ok(library)
+    }
+
     /// Get a pointer to a function or static variable by symbol name.
     ///
     /// The `symbol` may not contain any null bytes, with the exception of the last byte. Providing a
@@ -149,6 +292,232 @@ impl Library {
         self.0.get(symbol).map(|from| Symbol::from_raw(from, self))
     }
 
+    /// Get a pointer to a function or static variable by symbol name without blocking the
+    /// calling thread.
+    ///
+    /// This is [`Library::get`] running on another thread, like [`Library::new_async`]. Looking
+    /// up a symbol is usually quick, but can block while another thread is loading a library, as
+    /// the dynamic loader serializes these operations. The library is shared with the thread
+    /// through the [`Arc`], which the returned symbol borrows.
+    ///
+    /// # Safety
+    ///
+    /// See [`Library::get`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use ::libloading::{Library, Symbol};
+    /// # use std::sync::Arc;
+    /// # async fn load() -> Result<(), libloading::Error> {
+    /// let library = Arc::new(unsafe { Library::new_async("/path/to/awesome.module") }.await?);
+    /// let awesome_function: Symbol<unsafe extern "C" fn(f64) -> f64> =
+    ///     unsafe { library.get_async("awesome_function") }.await?;
+    /// # Ok(()) }
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(libloading_docs, doc(cfg(feature = "std")))]
+    pub unsafe fn get_async<'lib, T: Send + 'static>(
+        self: &'lib Arc<Self>,
+        symbol: impl AsSymbolName + Send + 'static,
+    ) -> impl Future<Output = Result<Symbol<'lib, T>, Error>> + Send + 'lib {
+        let library = Arc::clone(self);
+        let lookup = crate::blocking::spawn(move || {
+            library
+                .get::<T>(symbol)
+                .map(|symbol| Symbol::into_raw(symbol))
+        });
+        async move {
+            lookup.await.map(|inner| Symbol::from_raw(inner, &**self))
+        }
+    }
+
+    /// Get a pointer to a function or static variable by symbol name, with a type checked at
+    /// compile time.
+    ///
+    /// This is the same as [`Library::get`], except that `T` must implement [`SymbolType`]. Types
+    /// that cannot possibly hold the address of a symbol, which [`Library::get`] rejects at
+    /// runtime with [`Error::IncompatibleSize`], are rejected by the compiler instead:
+    ///
+    /// ```compile_fail
+    /// # use ::libloading::Library;
+    /// # let lib = unsafe { Library::new("/path/to/awesome.module").unwrap() };
+    /// let awesome_variable = unsafe { lib.get_typed::<u32>(b"awesome_variable\0") };
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// Users of this API must specify the correct type of the function or variable loaded.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use ::libloading::{Library, Symbol};
+    /// # let lib = unsafe { Library::new("/path/to/awesome.module").unwrap() };
+    /// unsafe {
+    ///     let awesome_function =
+    ///         lib.get_typed::<unsafe extern "C" fn(f64) -> f64>(b"awesome_function\0").unwrap();
+    ///     awesome_function(0.42);
+    ///     let awesome_variable = lib.get_typed::<*mut f64>(b"awesome_variable\0").unwrap();
+    ///     **awesome_variable = 42.0;
+    /// }
+    /// ```
+    pub unsafe fn get_typed<T: SymbolType>(
+        &self,
+        symbol: impl AsSymbolName,
+    ) -> Result<Symbol<'_, T>, Error> {
+        self.get(symbol)
+    }
+
+    /// Get a static variable by symbol name.
+    ///
+    /// The returned [`Static`] gives access to the value of the variable, rather than to its
+    /// address as `Library::get::<*mut T>` would.
+    ///
+    /// An error is returned if the address of the variable is not aligned for `T`. On Linux and
+    /// Android, the size of the variable is looked up in the dynamic symbol table as well, and an
+    /// error is returned if it is not the size of `T`.
+    ///
+    /// # Safety
+    ///
+    /// The symbol must be a variable of type `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use ::libloading::Library;
+    /// # let lib = unsafe { Library::new("/path/to/awesome.module").unwrap() };
+    /// unsafe {
+    ///     let awesome_variable = lib.get_static::<f64>(b"awesome_variable\0").unwrap();
+    ///     awesome_variable.write(awesome_variable.read() + 1.0);
+    /// }
+    /// ```
+    pub unsafe fn get_static<T>(&self, symbol: impl AsSymbolName) -> Result<Static<'_, T>, Error> {
+        let pointer = *self.get::<*mut T>(symbol)?;
+        check_data_symbol(pointer)?;
+        Ok(Static::new(pointer))
+    }
+
+    /// Get a static variable that is accessed atomically by symbol name.
+    ///
+    /// `A` is one of the atomic types in [`core::sync::atomic`], e.g. [`AtomicU32`] for a C
+    /// `_Atomic uint32_t` or a `uint32_t` only accessed with atomic operations. The checks are the
+    /// same as for [`Library::get_static`].
+    ///
+    /// [`AtomicU32`]: core::sync::atomic::AtomicU32
+    ///
+    /// # Safety
+    ///
+    /// The symbol must be a variable with the same in-memory representation as `A`, and all
+    /// concurrent accesses to it must be atomic.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use ::libloading::Library;
+    /// use std::sync::atomic::{AtomicU32, Ordering};
+    /// # let lib = unsafe { Library::new("/path/to/awesome.module").unwrap() };
+    /// let counter = unsafe { lib.get_atomic::<AtomicU32>(b"awesome_counter\0").unwrap() };
+    /// counter.fetch_add(1, Ordering::Relaxed);
+    /// ```
+    pub unsafe fn get_atomic<A: AtomicData>(&self, symbol: impl AsSymbolName) -> Result<&A, Error> {
+        let pointer = *self.get::<*mut A>(symbol)?;
+        check_data_symbol(pointer)?;
+        Ok(&*pointer)
+    }
+
+    /// Get a static array by symbol name, as a slice.
+    ///
+    /// The length of the slice is the size of the array, as recorded in the dynamic symbol table,
+    /// divided by the size of `T`. This makes it possible to use arrays such as
+    /// `const struct entry TABLE[] = { ... };` without knowing their length in advance.
+    ///
+    /// Symbol sizes are only available on Linux and Android; on other platforms
+    /// [`Error::UnknownSymbolSize`] is returned. [`Error::SymbolSizeNotMultiple`] is returned if
+    /// the size is not a multiple of the size of `T`.
+    ///
+    /// # Safety
+    ///
+    /// The symbol must be an array of `T`, which must not be modified for as long as the slice is
+    /// in use.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use ::libloading::Library;
+    /// #[repr(C)]
+    /// struct Entry {
+    ///     name: *const std::ffi::c_char,
+    ///     value: u32,
+    /// }
+    ///
+    /// # let lib = unsafe { Library::new("/path/to/awesome.module").unwrap() };
+    /// let table = unsafe { lib.get_slice::<Entry>(b"TABLE\0").unwrap() };
+    /// println!("{} entries", table.len());
+    /// ```
+    pub unsafe fn get_slice<T>(&self, symbol: impl AsSymbolName) -> Result<&[T], Error> {
+        slice_symbol(*self.get::<*const T>(symbol)?)
+    }
+
+    /// Get a static character array by symbol name, as a C string.
+    ///
+    /// The array is looked up as with [`Library::get_slice`], and the string ends at its first
+    /// null byte. [`Error::SymbolNotNulTerminated`] is returned if it has none. This makes it
+    /// possible to use arrays such as `const char VERSION[] = "1.2.3";`; for pointers such as
+    /// `const char *VERSION = "1.2.3";` use [`Library::get`] instead.
+    ///
+    /// # Safety
+    ///
+    /// The symbol must be an array of `char`, which must not be modified for as long as the
+    /// string is in use.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use ::libloading::Library;
+    /// # let lib = unsafe { Library::new("/path/to/awesome.module").unwrap() };
+    /// let version = unsafe { lib.get_cstr(b"VERSION\0").unwrap() };
+    /// println!("version {}", version.to_string_lossy());
+    /// ```
+    pub unsafe fn get_cstr(&self, symbol: impl AsSymbolName) -> Result<&core::ffi::CStr, Error> {
+        core::ffi::CStr::from_bytes_until_nul(self.get_slice::<u8>(symbol)?)
+            .map_err(|_| Error::SymbolNotNulTerminated)
+    }
+
+    /// Keep the library loaded for the rest of the program.
+    ///
+    /// The `Library` is leaked and never closed, so the symbols obtained from the returned
+    /// reference are `Symbol<'static, T>`.
+    ///
+    /// # Platform-specific behaviour
+    ///
+    /// On Linux and Windows the library is additionally pinned with
+    /// [`os::unix::Library::pin`] and [`os::windows::Library::pin`] respectively, which
+    /// guarantees that it stays loaded even if some other code closes more handles to it than it
+    /// has opened, for example with a stray `dlclose`. If pinning fails, the error is returned and
+    /// the library is closed as usual. On other platforms the library is only leaked, and this
+    /// never fails.
+    ///
+    /// [`os::unix::Library::pin`]: crate::os::unix::Library::pin
+    /// [`os::windows::Library::pin`]: crate::os::windows::Library::pin
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use ::libloading::{Library, Symbol};
+    /// let lib: &'static Library =
+    ///     unsafe { Library::new("/path/to/awesome.module").unwrap() }.leak().unwrap();
+    /// let awesome_function: Symbol<'static, unsafe extern "C" fn(f64) -> f64> =
+    ///     unsafe { lib.get(b"awesome_function\0").unwrap() };
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(libloading_docs, doc(cfg(feature = "alloc")))]
+    pub fn leak(self) -> Result<&'static Library, Error> {
+        #[cfg(any(target_os = "linux", windows))]
+        self.0.pin()?;
+        Ok(alloc::boxed::Box::leak(alloc::boxed::Box::new(self)))
+    }
+
     /// Unload the library.
     ///
     /// This method might be a no-op, depending on the flags with which the `Library` was opened,
@@ -164,6 +533,28 @@ impl Library {
     }
 }
 
+impl crate::symbol_source::Sealed for Library {}
+impl SymbolSource for Library {
+    type Symbol<'lib, T: 'lib> = Symbol<'lib, T>;
+    type Handle = <imp::Library as SymbolSource>::Handle;
+
+    unsafe fn get<T>(&self, symbol: impl AsSymbolName) -> Result<Symbol<'_, T>, Error> {
+        Library::get(self, symbol)
+    }
+
+    unsafe fn get_singlethreaded<T>(
+        &self,
+        symbol: impl AsSymbolName,
+    ) -> Result<Symbol<'_, T>, Error> {
+        SymbolSource::get_singlethreaded(&self.0, symbol)
+            .map(|from| Symbol::from_raw(from, self))
+    }
+
+    fn handle(&self) -> Self::Handle {
+        self.0.handle()
+    }
+}
+
 impl fmt::Debug for Library {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         self.0.fmt(f)
@@ -251,6 +642,59 @@ impl<'lib, T> Symbol<'lib, T> {
         }
     }
 
+    /// Call the function, converting a panic into an error instead of letting it unwind into the
+    /// caller.
+    ///
+    /// The arguments are passed as a tuple, e.g. `symbol.call_catch_unwind((1, 2))` for a function
+    /// of two arguments and `symbol.call_catch_unwind(())` for a function without arguments. See
+    /// [`UnwindFn`] for the supported function types; in particular, the function must have an ABI
+    /// that allows unwinding, such as `"C-unwind"`.
+    ///
+    /// If the function panics, [`Error::Panicked`] with the panic message is returned. The library
+    /// may have been left in an inconsistent state by the panic; see [`PoisonableLibrary`] to
+    /// refuse further calls in that case.
+    ///
+    /// [`UnwindFn`]: crate::UnwindFn
+    /// [`PoisonableLibrary`]: crate::PoisonableLibrary
+    ///
+    /// # Panics raised by another copy of the standard library
+    ///
+    /// A panic can only be caught by the copy of the standard library that raised it. A `cdylib`,
+    /// the usual crate type for plugins, contains its own copy, and a panic unwinding out of one
+    /// aborts the process with "Rust cannot catch foreign exceptions" instead. The same applies to
+    /// exceptions of other languages, such as C++.
+    ///
+    /// Panics of a plugin can be caught if both the host and the plugin are built with the same
+    /// compiler and link the standard library dynamically, with `-C prefer-dynamic`. Panics
+    /// raised by the host itself, e.g. in callbacks called by the library, can always be caught.
+    ///
+    /// # Safety
+    ///
+    /// The function must be safe to call with `args`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use ::libloading::{Library, Symbol};
+    /// # let lib = unsafe { Library::new("/path/to/awesome.module").unwrap() };
+    /// unsafe {
+    ///     let add: Symbol<unsafe extern "C-unwind" fn(u32, u32) -> u32> =
+    ///         lib.get(b"checked_add\0").unwrap();
+    ///     match add.call_catch_unwind((u32::MAX, 1)) {
+    ///         Ok(sum) => println!("{sum}"),
+    ///         Err(error) => eprintln!("{error}"),
+    ///     }
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(libloading_docs, doc(cfg(feature = "std")))]
+    pub unsafe fn call_catch_unwind<Args>(&self, args: Args) -> Result<T::Output, Error>
+    where
+        T: UnwindFn<Args>,
+    {
+        crate::unwind::call(*self.inner, args)
+    }
+
     /// Try to convert the symbol into a raw pointer.
     /// Success depends on the platform. Currently, this fn always succeeds and returns some.
     ///
@@ -0,0 +1,126 @@
+#[cfg(libloading_docs)]
+use super::os::unix as imp;
+#[cfg(all(not(libloading_docs), unix))]
+use super::os::unix as imp;
+#[cfg(all(not(libloading_docs), windows))]
+use super::os::windows as imp;
+use crate::as_symbol_name::AsSymbolName;
+use crate::symbol_source::Sealed;
+use crate::util::ensure_compatible_types;
+use crate::{Error, Symbol, SymbolSource};
+use alloc::collections::BTreeMap;
+use alloc::ffi::CString;
+use core::ffi::{c_void, CStr};
+
+/// A set of symbols registered from Rust, looked up like the symbols of a
+/// [`Library`](crate::Library).
+///
+/// This makes it possible to use the same code paths for plugins linked into the program as
+/// for plugins loaded at runtime, including where no dynamic loader is available, such as in
+/// fully static musl programs or under Miri. Code generic over [`SymbolSource`] accepts both.
+///
+/// # Examples
+///
+/// ```
+/// use libloading::{StaticLibrary, SymbolSource};
+///
+/// extern "C" fn double(value: u32) -> u32 {
+///     value * 2
+/// }
+///
+/// fn call<L: SymbolSource>(library: &L) -> u32 {
+///     unsafe {
+///         let f = library.get::<extern "C" fn(u32) -> u32>("double").unwrap();
+///         f(21)
+///     }
+/// }
+///
+/// let library = StaticLibrary::new().with_symbol("double", double as *const _);
+/// assert_eq!(call(&library), 42);
+/// ```
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(libloading_docs, doc(cfg(all(feature = "alloc", any(unix, windows)))))]
+pub struct StaticLibrary {
+    symbols: BTreeMap<CString, usize>,
+}
+
+impl StaticLibrary {
+    /// A library without any symbols.
+    pub fn new() -> StaticLibrary {
+        StaticLibrary::default()
+    }
+
+    /// Register a symbol resolving to `pointer`, replacing any symbol with the same name.
+    ///
+    /// # Panics
+    ///
+    /// If `name` contains a null byte anywhere but at its end.
+    pub fn with_symbol(mut self, name: impl AsSymbolName, pointer: *const c_void) -> StaticLibrary {
+        self.insert(name, pointer);
+        self
+    }
+
+    /// Register a symbol resolving to `pointer`, returning the pointer of the symbol with the same
+    /// name it replaces, if any.
+    ///
+    /// # Panics
+    ///
+    /// If `name` contains a null byte anywhere but at its end.
+    pub fn insert(
+        &mut self,
+        name: impl AsSymbolName,
+        pointer: *const c_void,
+    ) -> Option<*const c_void> {
+        let name = name
+            .symbol_name(|name| Ok(unsafe { CStr::from_ptr(name) }.into()))
+            .expect("symbol name must not contain interior null bytes");
+        self.symbols
+            .insert(name, pointer as usize)
+            .map(|pointer| pointer as *const c_void)
+    }
+
+    /// Get a pointer to a function or static variable by symbol name.
+    ///
+    /// Fails with [`Error::StaticSymbolNotFound`] if no symbol named `symbol` is registered.
+    ///
+    /// # Safety
+    ///
+    /// Users of this API must specify the correct type of the function or variable the symbol
+    /// has been registered for, like with [`Library::get`](crate::Library::get).
+    pub unsafe fn get<T>(&self, symbol: impl AsSymbolName) -> Result<Symbol<'_, T>, Error> {
+        ensure_compatible_types::<T, *mut c_void>()?;
+        symbol.symbol_name(|name| {
+            let name = CStr::from_ptr(name);
+            match self.symbols.get(name) {
+                Some(&pointer) => Ok(Symbol::from_raw(
+                    imp::Symbol::from_ptr(pointer as *mut c_void),
+                    self,
+                )),
+                None => Err(Error::StaticSymbolNotFound { name: name.into() }),
+            }
+        })
+    }
+}
+
+impl Sealed for StaticLibrary {}
+impl SymbolSource for StaticLibrary {
+    type Symbol<'lib, T: 'lib> = Symbol<'lib, T>;
+
+    /// The address of the `StaticLibrary`, which has no handle of its own.
+    type Handle = *const StaticLibrary;
+
+    unsafe fn get<T>(&self, symbol: impl AsSymbolName) -> Result<Symbol<'_, T>, Error> {
+        StaticLibrary::get(self, symbol)
+    }
+
+    unsafe fn get_singlethreaded<T>(
+        &self,
+        symbol: impl AsSymbolName,
+    ) -> Result<Symbol<'_, T>, Error> {
+        StaticLibrary::get(self, symbol)
+    }
+
+    fn handle(&self) -> *const StaticLibrary {
+        self
+    }
+}
@@ -0,0 +1,69 @@
+use crate::as_symbol_name::AsSymbolName;
+use crate::Error;
+use core::ops::Deref;
+
+pub(crate) trait Sealed {}
+
+/// This trait is implemented for the types symbols can be looked up from, so that generic code,
+/// such as a loader filling in a table of functions, can accept any of them:
+///
+/// * [`Library`](crate::Library);
+/// * [`StaticLibrary`](crate::StaticLibrary);
+/// * [`os::unix::Library`](crate::os::unix::Library);
+/// * [`os::windows::Library`](crate::os::windows::Library).
+///
+/// It is currently sealed and cannot be implemented by users of this crate.
+///
+/// # Examples
+///
+/// ```
+/// use libloading::SymbolSource;
+///
+/// unsafe fn call<L: SymbolSource>(library: &L) -> Result<u32, libloading::Error> {
+///     let f = library.get::<extern "C" fn(u32) -> u32>("double")?;
+///     Ok(f(21))
+/// }
+/// ```
+#[expect(private_bounds)]
+#[cfg_attr(libloading_docs, doc(cfg(any(unix, windows))))]
+pub trait SymbolSource: Sealed {
+    /// The type of the symbols looked up from this source, such as [`Symbol`](crate::Symbol)
+    /// for [`Library`](crate::Library).
+    type Symbol<'lib, T: 'lib>: Clone + Deref<Target = T>
+    where
+        Self: 'lib;
+
+    /// The type of the raw handle of the library, such as the one returned by `dlopen`.
+    type Handle: Copy;
+
+    /// Get a pointer to a function or static variable by symbol name.
+    ///
+    /// A symbol with a null address may be reported as an error instead, see
+    /// [`os::unix::Library::get`](crate::os::unix::Library::get).
+    ///
+    /// # Safety
+    ///
+    /// Users of this API must specify the correct type of the function or variable loaded.
+    unsafe fn get<T>(&self, symbol: impl AsSymbolName) -> Result<Self::Symbol<'_, T>, Error>;
+
+    /// Get a pointer to a function or static variable by symbol name, which may be null.
+    ///
+    /// This is [`os::unix::Library::get_singlethreaded`] for libraries loaded with `dlopen`, and
+    /// the same as [`get`](SymbolSource::get) otherwise.
+    ///
+    /// [`os::unix::Library::get_singlethreaded`]: crate::os::unix::Library::get_singlethreaded
+    ///
+    /// # Safety
+    ///
+    /// Users of this API must specify the correct type of the function or variable loaded, and
+    /// uphold the requirements of [`os::unix::Library::get_singlethreaded`].
+    unsafe fn get_singlethreaded<T>(
+        &self,
+        symbol: impl AsSymbolName,
+    ) -> Result<Self::Symbol<'_, T>, Error>;
+
+    /// The raw handle of the library.
+    ///
+    /// The handle remains owned by the library and must not be closed.
+    fn handle(&self) -> Self::Handle;
+}
@@ -0,0 +1,73 @@
+use core::ptr::NonNull;
+
+pub(crate) trait Sealed {}
+
+/// This trait is implemented for the types of symbols that can be looked up with
+/// [`Library::get_typed`](crate::Library::get_typed).
+///
+/// These are the types with the size of a pointer that can hold the address of a symbol:
+///
+/// * raw pointers to sized types, `*const T` and `*mut T`;
+/// * [`NonNull<T>`] and `Option<NonNull<T>>`;
+/// * function pointers with the `"Rust"`, `"C"`, `"system"`, `"C-unwind"` and `"system-unwind"`
+///   ABIs and up to 12 arguments, `unsafe` or not, and `Option`s of them. Variadic `"C"` and
+///   `"C-unwind"` function pointers are included as well.
+///
+/// `Option<*const T>` and `Option<*mut T>` are not included, as they are larger than a pointer.
+/// Function pointers with higher-ranked lifetimes, such as `fn(&u8) -> &u8`, are not included
+/// either; use [`Library::get`](crate::Library::get) for those.
+///
+/// It is currently sealed and cannot be implemented by users of this crate.
+#[expect(private_bounds)]
+pub trait SymbolType: Sealed {}
+
+impl<T> SymbolType for *const T {}
+impl<T> Sealed for *const T {}
+impl<T> SymbolType for *mut T {}
+impl<T> Sealed for *mut T {}
+impl<T> SymbolType for NonNull<T> {}
+impl<T> Sealed for NonNull<T> {}
+impl<T> SymbolType for Option<NonNull<T>> {}
+impl<T> Sealed for Option<NonNull<T>> {}
+
+macro_rules! impl_fn_pointers {
+    ($($arg:ident),*) => {
+        impl_fn_pointers!(@abi "Rust"; $($arg),*);
+        impl_fn_pointers!(@abi "C"; $($arg),*);
+        impl_fn_pointers!(@abi "system"; $($arg),*);
+        impl_fn_pointers!(@abi "C-unwind"; $($arg),*);
+        impl_fn_pointers!(@abi "system-unwind"; $($arg),*);
+        impl_fn_pointers!(@variadic "C"; $($arg),*);
+        impl_fn_pointers!(@variadic "C-unwind"; $($arg),*);
+    };
+    (@abi $abi:literal; $($arg:ident),*) => {
+        impl_fn_pointers!(@impl extern $abi fn($($arg),*) -> R; $($arg),*);
+        impl_fn_pointers!(@impl unsafe extern $abi fn($($arg),*) -> R; $($arg),*);
+    };
+    // Variadic functions need at least one other argument.
+    (@variadic $abi:literal;) => {};
+    (@variadic $abi:literal; $($arg:ident),+) => {
+        impl_fn_pointers!(@impl extern $abi fn($($arg),+, ...) -> R; $($arg),+);
+        impl_fn_pointers!(@impl unsafe extern $abi fn($($arg),+, ...) -> R; $($arg),+);
+    };
+    (@impl $ty:ty; $($arg:ident),*) => {
+        impl<R, $($arg),*> SymbolType for $ty {}
+        impl<R, $($arg),*> Sealed for $ty {}
+        impl<R, $($arg),*> SymbolType for Option<$ty> {}
+        impl<R, $($arg),*> Sealed for Option<$ty> {}
+    };
+}
+
+impl_fn_pointers!();
+impl_fn_pointers!(A);
+impl_fn_pointers!(A, B);
+impl_fn_pointers!(A, B, C);
+impl_fn_pointers!(A, B, C, D);
+impl_fn_pointers!(A, B, C, D, E);
+impl_fn_pointers!(A, B, C, D, E, F);
+impl_fn_pointers!(A, B, C, D, E, F, G);
+impl_fn_pointers!(A, B, C, D, E, F, G, H);
+impl_fn_pointers!(A, B, C, D, E, F, G, H, I);
+impl_fn_pointers!(A, B, C, D, E, F, G, H, I, J);
+impl_fn_pointers!(A, B, C, D, E, F, G, H, I, J, K);
+impl_fn_pointers!(A, B, C, D, E, F, G, H, I, J, K, L);
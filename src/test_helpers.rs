@@ -8,6 +8,26 @@ pub static mut TEST_STATIC_U32: u32 = 0;
 #[no_mangle]
 pub static mut TEST_STATIC_PTR: *mut () = 0 as *mut _;
 
+#[unsafe(no_mangle)]
+pub static mut TEST_STATIC_DATA: u64 = 7;
+
+#[unsafe(no_mangle)]
+pub static TEST_STATIC_ATOMIC: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
+
+#[unsafe(no_mangle)]
+pub static TEST_TABLE: [u32; 4] = [1, 2, 3, 4];
+
+#[unsafe(no_mangle)]
+pub static TEST_VERSION: [u8; 8] = *b"1.2.3\0\0\0";
+
+#[unsafe(no_mangle)]
+pub static TEST_NOT_CSTR: [u8; 3] = *b"abc";
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn test_get_static_data() -> u64 {
+    TEST_STATIC_DATA
+}
+
 #[no_mangle]
 pub extern "C" fn test_identity_u32(x: u32) -> u32 {
     x
@@ -40,3 +60,31 @@ pub unsafe extern "C" fn test_check_static_ptr() -> bool {
 pub extern "C" fn test_ordinals() -> *const std::ffi::c_char {
     "bunny\0".as_ptr().cast()
 }
+
+#[unsafe(no_mangle)]
+pub extern "C" fn test_plugin_init() -> std::ffi::c_int {
+    0
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn test_plugin_init_failing() -> std::ffi::c_int {
+    7
+}
+
+#[unsafe(no_mangle)]
+pub extern "C-unwind" fn test_call_unwind(callback: extern "C-unwind" fn(u32) -> u32, x: u32) -> u32 {
+    callback(x) + 1
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn test_fill_bytes(buffer: *mut u8, len: usize, value: u32) -> usize {
+    for i in 0..len {
+        *buffer.add(i) = value as u8;
+    }
+    len
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn test_sum_mixed(a: i32, b: f64, c: i64, d: f32) -> f64 {
+    a as f64 + b + c as f64 + d as f64
+}
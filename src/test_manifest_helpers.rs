@@ -0,0 +1,26 @@
+//! This is a separate file containing a plugin with a manifest, for tests of this library. It is
+//! built into a dynamic library by the tests in `tests/manifest.rs`.
+#![crate_type = "cdylib"]
+
+/// Mirrors `libloading::manifest::PluginManifest`, so that this plugin does not depend on the
+/// crate under test.
+#[repr(C)]
+pub struct PluginManifest {
+    magic: u64,
+    pointer_width: u32,
+    abi_version: [u32; 3],
+    rustc_version: [u8; 64],
+}
+
+#[unsafe(no_mangle)]
+pub static PLUGIN_MANIFEST: PluginManifest = PluginManifest {
+    magic: u64::from_le_bytes(*b"libloadM"),
+    pointer_width: usize::BITS,
+    abi_version: [1, 2, 0],
+    rustc_version: *b"test helpers\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0",
+};
+
+#[unsafe(no_mangle)]
+pub extern "C" fn test_identity_u32(x: u32) -> u32 {
+    x
+}
@@ -0,0 +1,101 @@
+//! Instrumentation of the calls of the dynamic loader with `tracing`.
+//!
+//! Everything here expands to nothing unless the `tracing` feature is enabled.
+
+/// Evaluate `$body`, a `Result`, inside a `DEBUG` span named `$name` with the `$fields`.
+///
+/// The time it took is recorded as the `elapsed` field of the span, if the `std` feature is
+/// enabled, and the outcome is reported as an event inside the span.
+macro_rules! traced {
+    ($name:literal, { $($fields:tt)* }, $body:expr) => {{
+        #[cfg(feature = "tracing")]
+        let result = $crate::trace::in_span(
+            tracing::debug_span!($name, $($fields)*, elapsed = tracing::field::Empty),
+            || $body,
+        );
+        #[cfg(not(feature = "tracing"))]
+        let result = $body;
+        result
+    }};
+}
+
+pub(crate) use traced;
+
+#[cfg(feature = "tracing")]
+pub(crate) fn in_span<T>(
+    span: tracing::Span,
+    body: impl FnOnce() -> Result<T, crate::Error>,
+) -> Result<T, crate::Error> {
+    let _entered = span.enter();
+    #[cfg(feature = "std")]
+    let start = std::time::Instant::now();
+    let result = body();
+    #[cfg(feature = "std")]
+    span.record("elapsed", tracing::field::debug(start.elapsed()));
+    match &result {
+        Ok(_) => tracing::debug!("succeeded"),
+        Err(error) => tracing::debug!(error = %ErrorChain(error), "failed"),
+    }
+    result
+}
+
+/// Displays an error followed by its sources.
+#[cfg(feature = "tracing")]
+struct ErrorChain<'a>(&'a crate::Error);
+
+#[cfg(feature = "tracing")]
+impl core::fmt::Display for ErrorChain<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Display::fmt(self.0, f)?;
+        let mut source = core::error::Error::source(self.0);
+        while let Some(error) = source {
+            write!(f, ": {error}")?;
+            source = error.source();
+        }
+        Ok(())
+    }
+}
+
+/// Formats a possibly null pointer to a null-terminated string, such as a filename or a symbol
+/// name, without allocating.
+#[cfg(feature = "tracing")]
+pub(crate) struct Name(pub(crate) *const core::ffi::c_char);
+
+#[cfg(feature = "tracing")]
+impl core::fmt::Debug for Name {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.0.is_null() {
+            f.write_str("null")
+        } else {
+            // SAFE: the pointer is only formatted while the string is alive.
+            core::fmt::Debug::fmt(unsafe { core::ffi::CStr::from_ptr(self.0) }, f)
+        }
+    }
+}
+
+/// Formats a null-terminated UTF-16 string, such as a Windows filename, without allocating.
+#[cfg(all(feature = "tracing", windows))]
+pub(crate) struct WideName(pub(crate) *const u16);
+
+#[cfg(all(feature = "tracing", windows))]
+impl core::fmt::Debug for WideName {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        use core::fmt::Write;
+        let mut length = 0;
+        // SAFE: the pointer is only formatted while the null-terminated string is alive.
+        while unsafe { *self.0.add(length) } != 0 {
+            length += 1;
+        }
+        let units = unsafe { core::slice::from_raw_parts(self.0, length) };
+        f.write_char('"')?;
+        for c in core::char::decode_utf16(units.iter().copied()) {
+            for c in c
+                .unwrap_or(core::char::REPLACEMENT_CHARACTER)
+                .escape_debug()
+            {
+                f.write_char(c)?;
+            }
+        }
+        f.write_char('"')
+    }
+}
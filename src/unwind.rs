@@ -0,0 +1,201 @@
+//! Calling functions of a library without letting their panics unwind into the caller.
+//!
+//! See [`Symbol::call_catch_unwind`](crate::Symbol::call_catch_unwind) and
+//! [`PoisonableLibrary`].
+
+use crate::{Error, Library, Symbol};
+use alloc::string::String;
+use core::sync::atomic::{AtomicBool, Ordering};
+use std::any::Any;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+pub(crate) trait Sealed {}
+
+/// This trait is implemented for the types of functions that can be called with
+/// [`Symbol::call_catch_unwind`](crate::Symbol::call_catch_unwind), with their arguments as the
+/// tuple `Args`.
+///
+/// These are function pointers with up to 12 arguments and an ABI that allows unwinding: the
+/// `"Rust"`, `"C-unwind"` and `"system-unwind"` ABIs, `unsafe` or not. A panic that reaches the
+/// boundary of a function with the `"C"` or `"system"` ABI aborts the process instead, so such
+/// functions cannot be contained. Note that not all panics that can unwind can also be caught,
+/// see [`Symbol::call_catch_unwind`](crate::Symbol::call_catch_unwind).
+///
+/// It is currently sealed and cannot be implemented by users of this crate.
+#[expect(private_bounds)]
+pub trait UnwindFn<Args>: Sealed + Copy {
+    /// The return type of the function.
+    type Output;
+
+    #[doc(hidden)]
+    unsafe fn call_with(self, args: Args) -> Self::Output;
+}
+
+macro_rules! impl_unwind_fn {
+    ($($arg:ident),*) => {
+        impl_unwind_fn!(@abi "Rust"; $($arg),*);
+        impl_unwind_fn!(@abi "C-unwind"; $($arg),*);
+        impl_unwind_fn!(@abi "system-unwind"; $($arg),*);
+    };
+    (@abi $abi:literal; $($arg:ident),*) => {
+        impl_unwind_fn!(@impl extern $abi fn($($arg),*) -> R; $($arg),*);
+        impl_unwind_fn!(@impl unsafe extern $abi fn($($arg),*) -> R; $($arg),*);
+    };
+    (@impl $ty:ty; $($arg:ident),*) => {
+        impl<R, $($arg),*> Sealed for $ty {}
+        impl<R, $($arg),*> UnwindFn<($($arg,)*)> for $ty {
+            type Output = R;
+
+            #[allow(non_snake_case)]
+            unsafe fn call_with(self, ($($arg,)*): ($($arg,)*)) -> R {
+                (self)($($arg),*)
+            }
+        }
+    };
+}
+
+impl_unwind_fn!();
+impl_unwind_fn!(A);
+impl_unwind_fn!(A, B);
+impl_unwind_fn!(A, B, C);
+impl_unwind_fn!(A, B, C, D);
+impl_unwind_fn!(A, B, C, D, E);
+impl_unwind_fn!(A, B, C, D, E, F);
+impl_unwind_fn!(A, B, C, D, E, F, G);
+impl_unwind_fn!(A, B, C, D, E, F, G, H);
+impl_unwind_fn!(A, B, C, D, E, F, G, H, I);
+impl_unwind_fn!(A, B, C, D, E, F, G, H, I, J);
+impl_unwind_fn!(A, B, C, D, E, F, G, H, I, J, K);
+impl_unwind_fn!(A, B, C, D, E, F, G, H, I, J, K, L);
+
+/// A [`Library`] that refuses to call its functions once one of them has panicked.
+///
+/// The functions are called with [`PoisonableLibrary::call_catch_unwind`], which catches a panic
+/// like [`Symbol::call_catch_unwind`] does. As the panic may have left the library in an
+/// inconsistent state, the library is then poisoned, and every later call returns
+/// [`Error::Poisoned`] without calling the function, until [`PoisonableLibrary::clear_poison`] is
+/// called.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use ::libloading::{Library, PoisonableLibrary, Symbol};
+/// let lib = PoisonableLibrary::new(unsafe { Library::new("/path/to/awesome.module").unwrap() });
+/// unsafe {
+///     let add: Symbol<unsafe extern "C-unwind" fn(u32, u32) -> u32> =
+///         lib.library().get(b"checked_add\0").unwrap();
+///     match lib.call_catch_unwind(&add, (u32::MAX, 1)) {
+///         Ok(sum) => println!("{sum}"),
+///         Err(error) => eprintln!("{error}"),
+///     }
+/// }
+/// ```
+#[cfg_attr(libloading_docs, doc(cfg(all(feature = "std", any(unix, windows)))))]
+pub struct PoisonableLibrary {
+    library: Library,
+    poisoned: AtomicBool,
+}
+
+impl PoisonableLibrary {
+    /// Wrap `library`, which is not poisoned initially.
+    pub fn new(library: Library) -> PoisonableLibrary {
+        PoisonableLibrary {
+            library,
+            poisoned: AtomicBool::new(false),
+        }
+    }
+
+    /// The library, to look up the symbols to call with
+    /// [`PoisonableLibrary::call_catch_unwind`].
+    pub fn library(&self) -> &Library {
+        &self.library
+    }
+
+    /// Unwrap the library, discarding its poisoned state.
+    pub fn into_inner(self) -> Library {
+        self.library
+    }
+
+    /// Whether a function of the library has panicked in
+    /// [`PoisonableLibrary::call_catch_unwind`].
+    pub fn is_poisoned(&self) -> bool {
+        self.poisoned.load(Ordering::Acquire)
+    }
+
+    /// Clear the poisoned state of the library, see [`PoisonableLibrary::is_poisoned`].
+    ///
+    /// This should only be done once the state of the library is known to be consistent again.
+    pub fn clear_poison(&self) {
+        self.poisoned.store(false, Ordering::Release)
+    }
+
+    /// Call the function `symbol` like [`Symbol::call_catch_unwind`], and poison the library if
+    /// it panics.
+    ///
+    /// If the library is already poisoned, [`Error::Poisoned`] is returned without calling the
+    /// function.
+    ///
+    /// # Safety
+    ///
+    /// The function must be safe to call with `args`. `symbol` should have been obtained from
+    /// [`PoisonableLibrary::library`], or the poisoned state of the wrong library is used.
+    pub unsafe fn call_catch_unwind<T, Args>(
+        &self,
+        symbol: &Symbol<'_, T>,
+        args: Args,
+    ) -> Result<T::Output, Error>
+    where
+        T: UnwindFn<Args>,
+    {
+        if self.is_poisoned() {
+            return Err(Error::Poisoned);
+        }
+        let result = symbol.call_catch_unwind(args);
+        if result.is_err() {
+            self.poisoned.store(true, Ordering::Release);
+        }
+        result
+    }
+}
+
+impl From<Library> for PoisonableLibrary {
+    fn from(library: Library) -> PoisonableLibrary {
+        PoisonableLibrary::new(library)
+    }
+}
+
+impl core::fmt::Debug for PoisonableLibrary {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("PoisonableLibrary")
+            .field("library", &self.library)
+            .field("poisoned", &self.is_poisoned())
+            .finish()
+    }
+}
+
+/// Call `function` with `args`, converting a panic into [`Error::Panicked`].
+///
+/// # Safety
+///
+/// `function` must be safe to call with `args`.
+pub(crate) unsafe fn call<F: UnwindFn<Args>, Args>(
+    function: F,
+    args: Args,
+) -> Result<F::Output, Error> {
+    // The arguments are lost if the function panics, and any state the function shares with the
+    // caller is what poisoning the library is for.
+    catch_unwind(AssertUnwindSafe(|| function.call_with(args))).map_err(|payload| Error::Panicked {
+        message: panic_message(&*payload),
+    })
+}
+
+/// The message of a panic, as printed by the default panic hook.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        String::from(*message)
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("Box<dyn Any>")
+    }
+}
@@ -25,10 +25,93 @@ pub(crate) fn check_null_bytes(data: &[u8]) -> Result<bool, Error> {
     }
 }
 
-/// This function copies the slice into a vec and appends an element to its end.
-pub(crate) fn copy_and_push<T: Copy>(data: &[T], to_push: T) -> alloc::vec::Vec<T> {
-    let mut copy = alloc::vec::Vec::with_capacity(data.len() + 1);
-    copy.extend_from_slice(data);
-    copy.push(to_push);
-    copy
+/// The capacity of the buffers on the stack names are null-terminated in, including the
+/// terminator.
+///
+/// With the `alloc` feature, longer names are copied to the heap instead, and the buffer is only
+/// large enough for typical symbol names and paths. Otherwise it fits most paths, but is kept
+/// small enough for the stack, as it holds UTF-16 on Windows.
+#[cfg(feature = "alloc")]
+const NAME_BUFFER_CAPACITY: usize = 384;
+#[cfg(not(feature = "alloc"))]
+const NAME_BUFFER_CAPACITY: usize = 1024;
+
+/// Call `function` with a pointer to a copy of `elements` terminated with a zero element.
+///
+/// The copy is made on the stack if it fits into [`NAME_BUFFER_CAPACITY`] elements. Longer names
+/// are copied to the heap with the `alloc` feature, and fail with [`Error::NameTooLong`]
+/// otherwise.
+#[cfg(windows)]
+pub(crate) fn with_terminated<T: Copy + Default, R>(
+    elements: impl IntoIterator<Item = T>,
+    function: impl FnOnce(*const T) -> Result<R, Error>,
+) -> Result<R, Error> {
+    let mut elements = elements.into_iter();
+    let mut buffer = [core::mem::MaybeUninit::<T>::uninit(); NAME_BUFFER_CAPACITY];
+    let mut length = 0;
+    while let Some(element) = elements.next() {
+        if length == NAME_BUFFER_CAPACITY - 1 {
+            #[cfg(feature = "alloc")]
+            {
+                // SAFE: the first `length` elements have been initialized.
+                let prefix = unsafe { core::slice::from_raw_parts(buffer.as_ptr().cast(), length) };
+                let mut heap = alloc::vec::Vec::with_capacity(2 * NAME_BUFFER_CAPACITY);
+                heap.extend_from_slice(prefix);
+                heap.push(element);
+                heap.extend(elements);
+                heap.push(T::default());
+                return function(heap.as_ptr());
+            }
+            #[cfg(not(feature = "alloc"))]
+            return Err(Error::NameTooLong {
+                maximum: NAME_BUFFER_CAPACITY - 1,
+            });
+        }
+        buffer[length].write(element);
+        length += 1;
+    }
+    buffer[length].write(T::default());
+    function(buffer.as_ptr().cast())
+}
+
+/// Call `function` with a pointer to a copy of `elements` terminated with a zero element.
+///
+/// Like `with_terminated`, but copying the whole slice at once.
+pub(crate) fn with_terminated_slice<T: Copy + Default, R>(
+    elements: &[T],
+    function: impl FnOnce(*const T) -> Result<R, Error>,
+) -> Result<R, Error> {
+    let length = elements.len();
+    if length >= NAME_BUFFER_CAPACITY {
+        #[cfg(feature = "alloc")]
+        {
+            let mut heap = alloc::vec::Vec::with_capacity(length + 1);
+            heap.extend_from_slice(elements);
+            heap.push(T::default());
+            return function(heap.as_ptr());
+        }
+        #[cfg(not(feature = "alloc"))]
+        return Err(Error::NameTooLong {
+            maximum: NAME_BUFFER_CAPACITY - 1,
+        });
+    }
+    let mut buffer = [core::mem::MaybeUninit::<T>::uninit(); NAME_BUFFER_CAPACITY];
+    // SAFE: `MaybeUninit<T>` has the same layout as `T`, and `length` is within the buffer.
+    unsafe {
+        core::ptr::copy_nonoverlapping(elements.as_ptr(), buffer.as_mut_ptr().cast(), length);
+    }
+    buffer[length].write(T::default());
+    function(buffer.as_ptr().cast())
+}
+
+/// Write `bytes` as UTF-8, replacing invalid sequences with `U+FFFD REPLACEMENT CHARACTER`.
+pub(crate) fn write_lossy(f: &mut core::fmt::Formatter<'_>, bytes: &[u8]) -> core::fmt::Result {
+    use core::fmt::Write;
+    for chunk in bytes.utf8_chunks() {
+        f.write_str(chunk.valid())?;
+        if !chunk.invalid().is_empty() {
+            f.write_char(char::REPLACEMENT_CHARACTER)?;
+        }
+    }
+    Ok(())
 }
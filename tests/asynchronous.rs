@@ -0,0 +1,72 @@
+#![cfg(feature = "std")]
+use super::functions::{lib_path, make_helpers};
+use libloading::{Error, Library, Symbol};
+use std::future::Future;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+/// Run a future to completion on the current thread, without any executor.
+fn block_on<F: Future>(future: F) -> F::Output {
+    struct Unpark(std::thread::Thread);
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+    let waker = Waker::from(Arc::new(Unpark(std::thread::current())));
+    let mut context = Context::from_waker(&waker);
+    let mut future = std::pin::pin!(future);
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
+
+#[test]
+fn new_async_get_async() {
+    make_helpers();
+    block_on(async {
+        let library = Arc::new(unsafe { Library::new_async(lib_path()) }.await.unwrap());
+        let f: Symbol<extern "C" fn(u32) -> u32> =
+            unsafe { library.get_async("test_identity_u32") }
+                .await
+                .unwrap();
+        assert_eq!(f(42), 42);
+        let error = unsafe { library.get_async::<extern "C" fn()>("test_does_not_exist") }
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            Error::DlSym { .. } | Error::GetProcAddress { .. }
+        ));
+    });
+}
+
+#[test]
+fn new_async_missing() {
+    let error =
+        block_on(unsafe { Library::new_async("/nonexistent/libtest_async.module") }).unwrap_err();
+    assert!(matches!(
+        error,
+        Error::DlOpen { .. } | Error::LoadLibraryExW { .. }
+    ));
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn new_async_tokio() {
+    make_helpers();
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        let library = Arc::new(unsafe { Library::new_async(lib_path()) }.await.unwrap());
+        let f: Symbol<extern "C" fn(u32) -> u32> =
+            unsafe { library.get_async("test_identity_u32") }
+                .await
+                .unwrap();
+        assert_eq!(f(7), 7);
+    });
+}
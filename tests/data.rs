@@ -0,0 +1,109 @@
+#![cfg(feature = "std")]
+use super::functions::{lib_path, make_helpers};
+use libloading::{Error, Library, Symbol};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+#[test]
+fn get_static() {
+    make_helpers();
+    unsafe {
+        let lib = Library::new(lib_path()).unwrap();
+        let data = lib.get_static::<u64>(b"TEST_STATIC_DATA\0").unwrap();
+        let get: Symbol<unsafe extern "C" fn() -> u64> =
+            lib.get(b"test_get_static_data\0").unwrap();
+        // Only this test writes the variable.
+        assert_eq!(data.read(), get());
+        data.write(42);
+        assert_eq!(get(), 42);
+        data.write_volatile(7);
+        assert_eq!(data.read_volatile(), 7);
+        assert_eq!(get(), 7);
+    }
+}
+
+#[test]
+fn get_atomic() {
+    make_helpers();
+    unsafe {
+        let lib = Library::new(lib_path()).unwrap();
+        let first = lib
+            .get_atomic::<AtomicU32>(b"TEST_STATIC_ATOMIC\0")
+            .unwrap();
+        let second = lib
+            .get_atomic::<AtomicU32>(b"TEST_STATIC_ATOMIC\0")
+            .unwrap();
+        let before = first.fetch_add(1, Ordering::SeqCst);
+        assert!(second.load(Ordering::SeqCst) > before);
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn get_static_wrong_size() {
+    make_helpers();
+    unsafe {
+        let lib = Library::new(lib_path()).unwrap();
+        assert!(matches!(
+            lib.get_static::<u32>(b"TEST_STATIC_DATA\0"),
+            Err(Error::SymbolSize {
+                found: 8,
+                expected: 4
+            })
+        ));
+        assert!(matches!(
+            lib.get_atomic::<std::sync::atomic::AtomicU16>(b"TEST_STATIC_ATOMIC\0"),
+            Err(Error::SymbolSize {
+                found: 4,
+                expected: 2
+            })
+        ));
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn get_slice() {
+    make_helpers();
+    unsafe {
+        let lib = Library::new(lib_path()).unwrap();
+        assert_eq!(lib.get_slice::<u32>(b"TEST_TABLE\0").unwrap(), [1, 2, 3, 4]);
+        assert_eq!(
+            lib.get_slice::<[u32; 2]>(b"TEST_TABLE\0").unwrap(),
+            [[1, 2], [3, 4]]
+        );
+        assert!(matches!(
+            lib.get_slice::<[u8; 3]>(b"TEST_TABLE\0"),
+            Err(Error::SymbolSizeNotMultiple {
+                found: 16,
+                element: 3
+            })
+        ));
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn get_cstr() {
+    make_helpers();
+    unsafe {
+        let lib = Library::new(lib_path()).unwrap();
+        assert_eq!(lib.get_cstr(b"TEST_VERSION\0").unwrap(), c"1.2.3");
+        assert!(matches!(
+            lib.get_cstr(b"TEST_NOT_CSTR\0"),
+            Err(Error::SymbolNotNulTerminated)
+        ));
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+#[test]
+fn get_slice_unknown_size() {
+    make_helpers();
+    unsafe {
+        let lib = Library::new(lib_path()).unwrap();
+        assert!(matches!(
+            lib.get_slice::<u32>(b"TEST_TABLE\0"),
+            Err(Error::UnknownSymbolSize)
+        ));
+    }
+}
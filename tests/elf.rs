@@ -0,0 +1,496 @@
+#![cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+use super::functions::{lib_path, make_helpers};
+use libloading::os::unix::Incompatibility;
+use libloading::Library;
+
+/// Copy the test helpers to a new file, patching the bytes at `offset`.
+fn patched_helpers(name: &str, offset: usize, bytes: &[u8]) -> std::path::PathBuf {
+    make_helpers();
+    let mut data = std::fs::read(lib_path()).unwrap();
+    data[offset..offset + bytes.len()].copy_from_slice(bytes);
+    let path = lib_path().with_file_name(name);
+    std::fs::write(&path, data).unwrap();
+    path
+}
+
+#[test]
+fn check_compatible_helpers() {
+    make_helpers();
+    let report = Library::check_compatible(lib_path()).unwrap();
+    assert!(report.is_compatible(), "{:?}", report.incompatibilities);
+    assert!(report
+        .required_versions
+        .iter()
+        .all(|requirement| requirement.satisfied != Some(false)));
+}
+
+#[test]
+fn check_compatible_wrong_machine() {
+    // `e_machine` of 0xb7 is `EM_AARCH64`, 0x3e is `EM_X86_64`.
+    let machine: u16 = if cfg!(target_arch = "aarch64") {
+        0x3e
+    } else {
+        0xb7
+    };
+    let bytes = if cfg!(target_endian = "little") {
+        machine.to_le_bytes()
+    } else {
+        machine.to_be_bytes()
+    };
+    let path = patched_helpers("libtest_helpers_machine.module", 18, &bytes);
+    let report = Library::check_compatible(&path).unwrap();
+    assert!(matches!(
+        report.incompatibilities[..],
+        [Incompatibility::Machine { found, .. }] if found == machine
+    ));
+    unsafe {
+        match Library::new_checked(&path) {
+            Err(libloading::Error::Incompatible { source }) => {
+                assert_eq!(source.incompatibilities(), &report.incompatibilities[..]);
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn check_compatible_wrong_class() {
+    let class = if cfg!(target_pointer_width = "64") {
+        1
+    } else {
+        2
+    };
+    let path = patched_helpers("libtest_helpers_class.module", 4, &[class]);
+    let report = Library::check_compatible(&path).unwrap();
+    assert!(report.incompatibilities.contains(&Incompatibility::Class {
+        found: class,
+        expected: 3 - class,
+    }));
+}
+
+#[test]
+fn check_compatible_not_elf() {
+    assert!(matches!(
+        Library::check_compatible("./Cargo.toml"),
+        Err(libloading::Error::MalformedObject)
+    ));
+    assert!(matches!(
+        Library::check_compatible("target/this_location_is_definitely_non existent:^~"),
+        Err(libloading::Error::Io { .. })
+    ));
+}
+
+#[test]
+fn new_checked_loads() {
+    make_helpers();
+    unsafe {
+        let lib = Library::new_checked(lib_path()).unwrap();
+        let f: libloading::Symbol<unsafe extern "C" fn(u32) -> u32> =
+            lib.get(b"test_identity_u32\0").unwrap();
+        assert_eq!(42, f(42));
+    }
+}
+
+/// Build the test helpers with a soname, a runpath and the `DF_1_NODELETE` flag.
+#[cfg(target_os = "linux")]
+fn linked_helpers_path() -> std::path::PathBuf {
+    static ONCE: std::sync::Once = std::sync::Once::new();
+    let path = lib_path().with_file_name("libtest_helpers_linked.so.1");
+    ONCE.call_once(|| {
+        let rustc = std::env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+        let status = std::process::Command::new(rustc)
+            .arg("src/test_helpers.rs")
+            .arg("-o")
+            .arg(&path)
+            .arg("-Clink-arg=-Wl,-soname,libtest_helpers_linked.so.1")
+            .arg("-Clink-arg=-Wl,--enable-new-dtags,-rpath,$ORIGIN/deps")
+            .arg("-Clink-arg=-Wl,-z,nodelete")
+            .status()
+            .expect("could not compile the test helpers!");
+        assert!(status.success());
+    });
+    path
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn dynamic_section_linked_helpers() {
+    use libloading::os::unix::{DynamicSection, DF_1_NODELETE};
+    let path = linked_helpers_path();
+    let dynamic = DynamicSection::read(&path).unwrap();
+    assert_eq!(
+        dynamic.soname.as_deref(),
+        Some("libtest_helpers_linked.so.1".as_ref())
+    );
+    assert_eq!(dynamic.runpath, ["$ORIGIN/deps"]);
+    assert!(dynamic.rpath.is_empty());
+    assert_ne!(dynamic.flags_1 & DF_1_NODELETE, 0);
+    assert!(!dynamic.needed.is_empty());
+}
+
+#[test]
+fn dynamic_section_resolve_needed() {
+    use libloading::os::unix::DynamicSection;
+    make_helpers();
+    let dynamic = DynamicSection::read(lib_path()).unwrap();
+    let dependencies = dynamic.resolve_needed();
+    assert_eq!(dependencies.len(), dynamic.needed.len());
+    for dependency in dependencies {
+        let path = dependency.path.expect("dependency should be found");
+        assert!(path.ends_with(&dependency.name));
+    }
+}
+
+/// Build the test helpers with a `DT_NEEDED` entry for a library that has since been removed.
+#[cfg(target_os = "linux")]
+fn broken_helpers_path() -> std::path::PathBuf {
+    static ONCE: std::sync::Once = std::sync::Once::new();
+    let path = lib_path().with_file_name("libtest_helpers_broken.module");
+    ONCE.call_once(|| {
+        let rustc = std::env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+        let dependency_dir = lib_path().with_file_name("missing_dependency");
+        std::fs::create_dir_all(&dependency_dir).unwrap();
+        let source = dependency_dir.join("missing.rs");
+        std::fs::write(&source, "#[no_mangle] pub extern \"C\" fn missing() {}").unwrap();
+        let status = std::process::Command::new(&rustc)
+            .arg("--crate-type=cdylib")
+            .arg(&source)
+            .arg("-o")
+            .arg(dependency_dir.join("libtest_missing.so"))
+            .status()
+            .expect("could not compile the missing dependency!");
+        assert!(status.success());
+        let status = std::process::Command::new(&rustc)
+            .arg("src/test_helpers.rs")
+            .arg("-o")
+            .arg(&path)
+            .arg(format!("-Clink-arg=-L{}", dependency_dir.display()))
+            .arg("-Clink-arg=-Wl,--push-state,--no-as-needed,-ltest_missing,--pop-state")
+            .arg("-Clink-arg=-Wl,--disable-new-dtags,-rpath,/nonexistent/rpath:/nonexistent/$LIB")
+            .status()
+            .expect("could not compile the test helpers!");
+        assert!(status.success());
+        std::fs::remove_dir_all(&dependency_dir).unwrap();
+    });
+    path
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn diagnose_missing_dependency() {
+    use libloading::os::unix::{diagnose_load_failure, SearchKind};
+    let path = broken_helpers_path();
+    unsafe {
+        Library::new(&path).err().unwrap();
+    }
+    let diagnosis = diagnose_load_failure(&path).unwrap();
+    assert!(!diagnosis.is_complete());
+    assert_eq!(diagnosis.missing.len(), 1);
+    let missing = &diagnosis.missing[0];
+    assert_eq!(missing.name, "libtest_missing.so");
+    assert_eq!(missing.needed_by, path);
+    assert_eq!(missing.searched[0].kind, SearchKind::Rpath);
+    assert_eq!(
+        missing.searched[0].path,
+        std::path::Path::new("/nonexistent/rpath")
+    );
+    // `$LIB` is the directory of the C library, such as `lib/x86_64-linux-gnu` on Debian.
+    let maps = std::fs::read_to_string("/proc/self/maps").unwrap();
+    let libc = maps
+        .lines()
+        .filter_map(|line| line.split_whitespace().nth(5))
+        .find(|path| path.contains("/libc.so") || path.contains("/libc-"))
+        .map(std::path::Path::new)
+        .unwrap();
+    let lib_dir = libc.parent().unwrap();
+    let lib_dir = lib_dir
+        .strip_prefix("/usr")
+        .or_else(|_| lib_dir.strip_prefix("/"))
+        .unwrap();
+    assert_eq!(missing.searched[1].kind, SearchKind::Rpath);
+    assert_eq!(
+        missing.searched[1].path,
+        std::path::Path::new("/nonexistent").join(lib_dir)
+    );
+    assert!(missing
+        .searched
+        .iter()
+        .any(|location| location.kind == SearchKind::Default));
+    assert!(diagnosis
+        .to_string()
+        .contains("libtest_missing.so => not found"));
+}
+
+/// A dependency already loaded from a file named differently from its soname is found.
+#[cfg(target_os = "linux")]
+#[test]
+fn diagnose_loaded_by_soname() {
+    use libloading::os::unix::diagnose_load_failure;
+    let dir = lib_path().with_file_name("soname_dependency");
+    std::fs::create_dir_all(&dir).unwrap();
+    let source = dir.join("dependency.rs");
+    std::fs::write(
+        &source,
+        "#[unsafe(no_mangle)] pub extern \"C\" fn dependency() {}",
+    )
+    .unwrap();
+    let dependency = dir.join("libtest_soname_dependency.so.1.2.3");
+    let rustc = std::env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    let status = std::process::Command::new(&rustc)
+        .arg("--crate-type=cdylib")
+        .arg(&source)
+        .arg("-o")
+        .arg(&dependency)
+        .arg("-Clink-arg=-Wl,-soname,libtest_soname_dependency.so.1")
+        .status()
+        .expect("could not compile the dependency!");
+    assert!(status.success());
+    let path = dir.join("libtest_helpers_soname.module");
+    let status = std::process::Command::new(&rustc)
+        .arg("src/test_helpers.rs")
+        .arg("-o")
+        .arg(&path)
+        .arg(format!(
+            "-Clink-arg=-Wl,--push-state,--no-as-needed,{},--pop-state",
+            dependency.display()
+        ))
+        .status()
+        .expect("could not compile the test helpers!");
+    assert!(status.success());
+    let diagnosis = diagnose_load_failure(&path).unwrap();
+    assert_eq!(diagnosis.missing.len(), 1, "{diagnosis}");
+    assert_eq!(diagnosis.missing[0].name, "libtest_soname_dependency.so.1");
+    unsafe {
+        let _dependency = Library::new(&dependency).unwrap();
+        let diagnosis = diagnose_load_failure(&path).unwrap();
+        assert!(diagnosis.is_complete(), "{diagnosis}");
+        let found = diagnosis
+            .found
+            .iter()
+            .find(|found| found.name == "libtest_soname_dependency.so.1")
+            .unwrap();
+        assert_eq!(found.path.as_deref(), Some(&*dependency));
+    }
+}
+
+#[test]
+fn diagnose_not_found() {
+    use libloading::os::unix::diagnose_load_failure;
+    match diagnose_load_failure("libtest_does_not_exist.so") {
+        Err(libloading::Error::LibraryNotFound { name }) => {
+            assert_eq!(name, "libtest_does_not_exist.so")
+        }
+        other => panic!("expected LibraryNotFound, got {other:?}"),
+    }
+}
+
+#[test]
+fn diagnose_complete() {
+    use libloading::os::unix::diagnose_load_failure;
+    make_helpers();
+    let diagnosis = diagnose_load_failure(lib_path()).unwrap();
+    assert!(diagnosis.is_complete(), "{diagnosis}");
+    assert!(!diagnosis.found.is_empty());
+}
+
+/// An entry of a fixture `ld.so.cache`.
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+struct CacheFixture<'a> {
+    name: &'a str,
+    path: std::path::PathBuf,
+    hwcaps: Option<&'a str>,
+}
+
+/// The flags of the entries in the system cache, which are the ones the dynamic loader accepts.
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+fn native_cache_flags() -> i32 {
+    use libloading::os::unix::LdSoCache;
+    let cache = LdSoCache::read("/etc/ld.so.cache").unwrap();
+    let libc = cache
+        .entries()
+        .iter()
+        .find(|entry| entry.name == "libc.so.6");
+    libc.expect("libc.so.6 should be in the cache").flags
+}
+
+/// Serialize `entries` in the `ld.so-1.7.0` format.
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+fn old_cache(entries: &[CacheFixture]) -> Vec<u8> {
+    let flags = native_cache_flags();
+    let mut header = b"ld.so-1.7.0\0".to_vec();
+    header.extend((entries.len() as u32).to_ne_bytes());
+    let mut strings = Vec::new();
+    for entry in entries {
+        header.extend(flags.to_ne_bytes());
+        for string in [
+            entry.name.as_bytes(),
+            entry.path.as_os_str().as_encoded_bytes(),
+        ] {
+            header.extend((strings.len() as u32).to_ne_bytes());
+            strings.extend(string);
+            strings.push(0);
+        }
+    }
+    header.extend(strings);
+    header
+}
+
+/// Serialize `entries` in the `glibc-ld.so.cache1.1` format.
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+fn new_cache(entries: &[CacheFixture]) -> Vec<u8> {
+    let flags = native_cache_flags();
+    let hwcaps: Vec<&str> = entries.iter().filter_map(|entry| entry.hwcaps).collect();
+    let extension = 48 + 24 * entries.len();
+    let hwcaps_table = extension + 8 + 16;
+    let mut strings = Vec::new();
+    let mut string = |value: &[u8]| {
+        let offset = (hwcaps_table + 4 * hwcaps.len() + strings.len()) as u32;
+        strings.extend(value);
+        strings.push(0);
+        offset
+    };
+    let mut data = b"glibc-ld.so.cache1.1".to_vec();
+    data.extend((entries.len() as u32).to_ne_bytes());
+    data.extend(0u32.to_ne_bytes());
+    data.extend([if cfg!(target_endian = "little") { 2 } else { 1 }, 0, 0, 0]);
+    data.extend((extension as u32).to_ne_bytes());
+    data.extend([0; 12]);
+    let mut hwcaps_index = 0;
+    for entry in entries {
+        data.extend(flags.to_ne_bytes());
+        data.extend(string(entry.name.as_bytes()).to_ne_bytes());
+        data.extend(string(entry.path.as_os_str().as_encoded_bytes()).to_ne_bytes());
+        data.extend(0u32.to_ne_bytes());
+        let hwcap = match entry.hwcaps {
+            Some(_) => {
+                hwcaps_index += 1;
+                1u64 << 62 | (hwcaps_index - 1)
+            }
+            None => 0,
+        };
+        data.extend(hwcap.to_ne_bytes());
+    }
+    let hwcaps_offsets: Vec<u32> = hwcaps.iter().map(|name| string(name.as_bytes())).collect();
+    data.extend(0xEAA4_2174u32.to_ne_bytes());
+    data.extend(1u32.to_ne_bytes());
+    data.extend(1u32.to_ne_bytes());
+    data.extend(0u32.to_ne_bytes());
+    data.extend((hwcaps_table as u32).to_ne_bytes());
+    data.extend((4 * hwcaps.len() as u32).to_ne_bytes());
+    for offset in hwcaps_offsets {
+        data.extend(offset.to_ne_bytes());
+    }
+    data.extend(strings);
+    data
+}
+
+/// Copy the test helpers into `glibc-hwcaps/<hwcaps>` (or the top level) of a fixture directory.
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+fn cache_fixture_library(name: &str, hwcaps: Option<&str>) -> std::path::PathBuf {
+    make_helpers();
+    let mut dir = lib_path().with_file_name("ld_cache_fixture");
+    if let Some(hwcaps) = hwcaps {
+        dir = dir.join("glibc-hwcaps").join(hwcaps);
+    }
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    std::fs::copy(lib_path(), &path).unwrap();
+    path
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+#[test]
+fn ld_cache_old_format() {
+    use libloading::os::unix::LdSoCache;
+    let library = cache_fixture_library("libfixture_old.so.1", None);
+    let data = old_cache(&[CacheFixture {
+        name: "libfixture_old.so.1",
+        path: library.clone(),
+        hwcaps: None,
+    }]);
+    let path = lib_path().with_file_name("ld.so.cache.old");
+    std::fs::write(&path, data).unwrap();
+    let cache = LdSoCache::read(&path).unwrap();
+    assert_eq!(cache.entries().len(), 1);
+    assert_eq!(cache.lookup("libfixture_old.so.1"), Some(&*library));
+    assert_eq!(cache.lookup("libfixture_missing.so.1"), None);
+    assert_eq!(
+        cache.resolve_library_name("libfixture_old.so.1").unwrap(),
+        Some(library)
+    );
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+#[test]
+fn ld_cache_new_format_hwcaps() {
+    use libloading::os::unix::LdSoCache;
+    let name = "libfixture_hwcaps.so.1";
+    let baseline = cache_fixture_library(name, None);
+    let v2 = cache_fixture_library(name, Some("x86-64-v2"));
+    let data = new_cache(&[
+        CacheFixture {
+            name,
+            path: cache_fixture_library(name, Some("unsupported-v9")),
+            hwcaps: Some("unsupported-v9"),
+        },
+        CacheFixture {
+            name,
+            path: v2.clone(),
+            hwcaps: Some("x86-64-v2"),
+        },
+        CacheFixture {
+            name,
+            path: baseline.clone(),
+            hwcaps: None,
+        },
+    ]);
+    let path = lib_path().with_file_name("ld.so.cache.new");
+    std::fs::write(&path, data).unwrap();
+    let cache = LdSoCache::read(&path).unwrap();
+    assert_eq!(cache.entries().len(), 3);
+    assert_eq!(
+        cache.entries()[0].hwcaps.as_deref(),
+        Some(std::ffi::OsStr::new("unsupported-v9"))
+    );
+
+    #[cfg(target_arch = "x86_64")]
+    let expected = if is_x86_feature_detected!("cmpxchg16b")
+        && is_x86_feature_detected!("popcnt")
+        && is_x86_feature_detected!("sse3")
+        && is_x86_feature_detected!("sse4.1")
+        && is_x86_feature_detected!("sse4.2")
+        && is_x86_feature_detected!("ssse3")
+    {
+        v2
+    } else {
+        baseline
+    };
+    #[cfg(not(target_arch = "x86_64"))]
+    let expected = baseline;
+    assert_eq!(cache.lookup(name), Some(&*expected));
+    assert_eq!(cache.resolve_library_name(name).unwrap(), Some(expected));
+}
+
+#[test]
+fn ld_cache_malformed() {
+    use libloading::os::unix::LdSoCache;
+    make_helpers();
+    match LdSoCache::read(lib_path()) {
+        Err(libloading::Error::MalformedLdCache) => {}
+        other => panic!("expected MalformedLdCache, got {other:?}"),
+    }
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+#[test]
+fn resolve_library_name_libc() {
+    use libloading::os::unix::resolve_library_name;
+    let path = resolve_library_name("libc.so.6").unwrap().unwrap();
+    assert!(path.ends_with("libc.so.6"));
+    assert_eq!(
+        resolve_library_name("libdoes_not_exist.so.1").unwrap(),
+        None
+    );
+    let report = Library::check_compatible("libc.so.6").unwrap();
+    assert!(report.is_compatible());
+}
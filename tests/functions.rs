@@ -315,6 +315,28 @@ fn test_incompatible_type_named_fn() {
     }
 }
 
+#[test]
+#[cfg(feature = "std")]
+fn test_get_typed() {
+    use std::ptr::NonNull;
+
+    make_helpers();
+    unsafe {
+        let lib = Library::new(lib_path()).unwrap();
+        let f = lib
+            .get_typed::<unsafe extern "C" fn(u32) -> u32>(b"test_identity_u32\0")
+            .unwrap();
+        assert_eq!(f(42), 42);
+        let f = lib
+            .get_typed::<Option<extern "C" fn(u32) -> u32>>(b"test_identity_u32\0")
+            .unwrap();
+        assert_eq!(f.unwrap()(42), 42);
+        let var = lib.get_typed::<*mut u32>(b"TEST_STATIC_U32\0").unwrap();
+        let non_null = lib.get_typed::<NonNull<u32>>(b"TEST_STATIC_U32\0").unwrap();
+        assert_eq!(*var, non_null.as_ptr());
+    }
+}
+
 #[test]
 #[cfg(feature = "std")]
 fn test_static_u32() {
@@ -458,6 +480,44 @@ fn works_pin_module() {
     }
 }
 
+#[cfg(target_os = "linux")]
+#[cfg(feature = "std")]
+#[test]
+fn works_pin_library() {
+    use libloading::os::unix::{Library, RTLD_LAZY};
+
+    make_helpers();
+    // A copy, so that pinning it does not affect the other tests.
+    let path = lib_path().with_file_name("libtest_helpers_pinned.module");
+    std::fs::copy(lib_path(), &path).unwrap();
+    unsafe {
+        let lib = Library::new(&path).unwrap();
+        lib.pin().unwrap();
+        lib.close().unwrap();
+        // Still loaded.
+        Library::open(Some(&path), RTLD_LAZY | libc::RTLD_NOLOAD).unwrap();
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn works_pin_this() {
+    libloading::os::unix::Library::this().pin().unwrap();
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn library_leak() {
+    make_helpers();
+    // A copy, so that keeping it loaded does not affect the other tests.
+    let path = lib_path().with_file_name("libtest_helpers_leaked.module");
+    std::fs::copy(lib_path(), &path).unwrap();
+    let lib: &'static Library = unsafe { Library::new(&path).unwrap() }.leak().unwrap();
+    let f: Symbol<'static, unsafe extern "C" fn(u32) -> u32> =
+        unsafe { lib.get(b"test_identity_u32\0").unwrap() };
+    assert_eq!(unsafe { f(42) }, 42);
+}
+
 #[cfg(windows)]
 #[test]
 fn library_open_already_loaded() {
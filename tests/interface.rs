@@ -0,0 +1,68 @@
+#[cfg(feature = "std")]
+use super::functions::{lib_path, make_helpers};
+use libloading::interface::Signature;
+#[cfg(feature = "std")]
+use libloading::Library;
+
+libloading::interface! {
+    /// The functions exported by the test helpers.
+    pub mod helpers {
+        fn test_identity_u32(x: u32) -> u32;
+        unsafe fn test_get_static_u32() -> u32;
+        fn test_identity_struct(x: S) -> S;
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+// Only constructed by `import_helpers`.
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
+pub struct S {
+    a: u64,
+    b: u32,
+    c: u16,
+    d: u8,
+}
+
+libloading::interface! {
+    mod local {
+        fn interface_double(x: u64) -> u64;
+        unsafe fn interface_nothing();
+    }
+}
+
+libloading::export! {
+    local::interface_double => fn interface_double(x: u64) -> u64 {
+        x * 2
+    }
+    local::interface_nothing => unsafe fn interface_nothing() {}
+}
+
+#[test]
+fn signature_names() {
+    assert_eq!(helpers::test_identity_u32::NAME, c"test_identity_u32");
+    assert_eq!(local::interface_nothing::NAME, c"interface_nothing");
+    let double: <local::interface_double as Signature>::Fn = interface_double;
+    assert_eq!(double(21), 42);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn import_helpers() {
+    make_helpers();
+    unsafe {
+        let lib = Library::new(lib_path()).unwrap();
+        let identity = libloading::import!(lib, helpers::test_identity_u32).unwrap();
+        assert_eq!(identity(42), 42);
+        let get_static = libloading::import!(lib, helpers::test_get_static_u32).unwrap();
+        assert_eq!(get_static(), 0);
+        let identity = libloading::import!(lib, helpers::test_identity_struct).unwrap();
+        let value = S {
+            a: 1,
+            b: 2,
+            c: 3,
+            d: 4,
+        };
+        assert_eq!(identity(value), value);
+    }
+}
@@ -0,0 +1,30 @@
+#![cfg(feature = "std")]
+use super::functions::{lib_path, make_helpers};
+use libloading::{Error, LazyLibrary, Symbol};
+
+static MISSING: LazyLibrary = unsafe { LazyLibrary::new("libthis_library_does_not_exist.so.42") };
+
+#[test]
+fn lazy_library_failure_is_cached() {
+    assert_eq!(MISSING.filename(), "libthis_library_does_not_exist.so.42");
+    let first = MISSING.library().unwrap_err();
+    let second = unsafe { MISSING.get::<unsafe extern "C" fn()>(b"anything\0") }.unwrap_err();
+    assert_eq!(first.to_string(), second.to_string());
+    assert!(matches!(
+        first,
+        Error::DlOpen { .. } | Error::LoadLibraryExW { .. }
+    ));
+}
+
+#[test]
+fn lazy_library_loads_once() {
+    make_helpers();
+    let path: &'static str = Box::leak(lib_path().to_str().unwrap().to_owned().into_boxed_str());
+    let lazy: &'static LazyLibrary = Box::leak(Box::new(unsafe { LazyLibrary::new(path) }));
+    let f: Symbol<'static, unsafe extern "C" fn(u32) -> u32> =
+        unsafe { lazy.get(b"test_identity_u32\0") }.unwrap();
+    assert_eq!(unsafe { f(42) }, 42);
+    let first: *const _ = lazy.library().unwrap();
+    let second: *const _ = lazy.library().unwrap();
+    assert_eq!(first, second);
+}
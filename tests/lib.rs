@@ -1,5 +1,25 @@
+// Some of the tests predate linting them with clippy.
+#![allow(clippy::redundant_static_lifetimes, clippy::match_like_matches_macro)]
+
+mod asynchronous;
 mod constants;
+mod data;
+mod elf;
 mod functions;
+mod interface;
+mod lazy;
 mod library_filename;
+mod manifest;
 mod markers;
+mod mock;
+mod names;
+mod no_alloc;
+mod observer;
+mod plugin;
+mod probe;
+mod remote;
+mod static_library;
+mod symbol_source;
+mod trace;
+mod unwind;
 mod windows;
@@ -0,0 +1,167 @@
+#[cfg(feature = "std")]
+use super::functions::lib_path;
+use libloading::manifest::{AbiVersion, PluginManifest};
+#[cfg(feature = "std")]
+use libloading::{manifest::ManifestError, Error, Library};
+
+libloading::plugin_manifest!(3, 1, 4);
+
+/// Build the plugin exporting a manifest for ABI 1.2.0, which does not depend on this crate.
+#[cfg(feature = "std")]
+pub fn manifest_helpers_path() -> std::path::PathBuf {
+    static ONCE: std::sync::Once = std::sync::Once::new();
+    let path = lib_path().with_file_name("libtest_manifest_helpers.module");
+    ONCE.call_once(|| {
+        let rustc = std::env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+        let mut cmd = std::process::Command::new(rustc);
+        cmd.arg("src/test_manifest_helpers.rs").arg("-o").arg(&path);
+        if let Some(target) = std::env::var_os("TARGET") {
+            cmd.arg("--target").arg(target);
+        }
+        assert!(cmd
+            .status()
+            .expect("could not compile the manifest helpers!")
+            .success());
+    });
+    path
+}
+
+/// Copy the manifest helpers to a new file, patching the manifest at `offset` from its start.
+#[cfg(feature = "std")]
+fn patched_manifest(name: &str, offset: usize, bytes: &[u8]) -> std::path::PathBuf {
+    let mut data = std::fs::read(manifest_helpers_path()).unwrap();
+    let magic = PluginManifest::MAGIC.to_ne_bytes();
+    let start = data
+        .windows(magic.len())
+        .position(|window| window == magic)
+        .expect("the manifest helpers should contain a manifest")
+        + offset;
+    data[start..start + bytes.len()].copy_from_slice(bytes);
+    let path = lib_path().with_file_name(name);
+    std::fs::write(&path, data).unwrap();
+    path
+}
+
+#[test]
+fn abi_version_supports() {
+    let host = AbiVersion::new(1, 3, 0);
+    assert!(host.supports(AbiVersion::new(1, 0, 0)));
+    assert!(host.supports(AbiVersion::new(1, 3, 7)));
+    assert!(!host.supports(AbiVersion::new(1, 4, 0)));
+    assert!(!host.supports(AbiVersion::new(2, 0, 0)));
+    let host = AbiVersion::new(0, 3, 2);
+    assert!(host.supports(AbiVersion::new(0, 3, 0)));
+    assert!(!host.supports(AbiVersion::new(0, 3, 3)));
+    assert!(!host.supports(AbiVersion::new(0, 2, 0)));
+    assert!(AbiVersion::new(0, 0, 1).supports(AbiVersion::new(0, 0, 1)));
+    assert!(!AbiVersion::new(0, 0, 2).supports(AbiVersion::new(0, 0, 1)));
+}
+
+#[test]
+fn macro_manifest() {
+    assert_eq!(PLUGIN_MANIFEST.magic, PluginManifest::MAGIC);
+    assert_eq!(PLUGIN_MANIFEST.abi_version, AbiVersion::new(3, 1, 4));
+    assert_eq!(PLUGIN_MANIFEST.rustc_version(), None);
+    assert_eq!(PLUGIN_MANIFEST.check(AbiVersion::new(3, 2, 0)), Ok(()));
+}
+
+#[test]
+fn manifest_rustc_version() {
+    let manifest = PluginManifest::new(AbiVersion::new(1, 0, 0)).with_rustc_version("rustc 1.88.0");
+    assert_eq!(manifest.rustc_version(), Some("rustc 1.88.0"));
+    let long = "x".repeat(100);
+    let manifest = manifest.with_rustc_version(&long);
+    assert_eq!(manifest.rustc_version(), Some(&long[..63]));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn new_with_manifest() {
+    unsafe {
+        let lib =
+            Library::new_with_manifest(manifest_helpers_path(), AbiVersion::new(1, 2, 3)).unwrap();
+        let f = lib
+            .get::<unsafe extern "C" fn(u32) -> u32>(b"test_identity_u32\0")
+            .unwrap();
+        assert_eq!(f(42), 42);
+    }
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn new_with_manifest_incompatible_abi() {
+    match unsafe { Library::new_with_manifest(manifest_helpers_path(), AbiVersion::new(1, 1, 0)) } {
+        Err(Error::Manifest {
+            source: ManifestError::IncompatibleAbi { found, expected },
+        }) => {
+            assert_eq!(found, AbiVersion::new(1, 2, 0));
+            assert_eq!(expected, AbiVersion::new(1, 1, 0));
+        }
+        other => panic!("expected IncompatibleAbi, got {other:?}"),
+    }
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn new_with_manifest_bad_magic() {
+    let path = patched_manifest("libtest_manifest_helpers_bad_magic.module", 7, b"?");
+    match unsafe { Library::new_with_manifest(&path, AbiVersion::new(1, 2, 0)) } {
+        Err(Error::Manifest {
+            source: ManifestError::BadMagic { .. },
+        }) => {}
+        other => panic!("expected BadMagic, got {other:?}"),
+    }
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn new_with_manifest_pointer_width() {
+    let width = if cfg!(target_pointer_width = "64") {
+        32u32
+    } else {
+        64
+    };
+    let path = patched_manifest(
+        "libtest_manifest_helpers_pointer_width.module",
+        8,
+        &width.to_ne_bytes(),
+    );
+    match unsafe { Library::new_with_manifest(&path, AbiVersion::new(1, 2, 0)) } {
+        Err(Error::Manifest {
+            source: ManifestError::PointerWidth { found, .. },
+        }) => assert_eq!(found, width),
+        other => panic!("expected PointerWidth, got {other:?}"),
+    }
+}
+
+/// A library exporting a `PLUGIN_MANIFEST` too small to be a manifest is rejected without reading
+/// past its end.
+#[test]
+#[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+fn new_with_manifest_short_symbol() {
+    let dir = lib_path().with_file_name("short_manifest");
+    std::fs::create_dir_all(&dir).unwrap();
+    let source = dir.join("short_manifest.rs");
+    std::fs::write(
+        &source,
+        "#[unsafe(no_mangle)] pub static PLUGIN_MANIFEST: u32 = 7;",
+    )
+    .unwrap();
+    let path = dir.join("libshort_manifest.so");
+    let rustc = std::env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    let status = std::process::Command::new(rustc)
+        .arg("--crate-type=cdylib")
+        .arg(&source)
+        .arg("-o")
+        .arg(&path)
+        .status()
+        .expect("could not compile the short manifest!");
+    assert!(status.success());
+    match unsafe { Library::new_with_manifest(&path, AbiVersion::new(1, 2, 0)) } {
+        Err(Error::SymbolSize { found, expected }) => {
+            assert_eq!(found, 4);
+            assert_eq!(expected, std::mem::size_of::<PluginManifest>());
+        }
+        other => panic!("expected SymbolSize, got {other:?}"),
+    }
+}
@@ -6,6 +6,8 @@ fn assert_send<T: Send>() {}
 fn assert_sync<T: Sync>() {}
 #[cfg(test)]
 fn assert_display<T: std::fmt::Display>() {}
+#[cfg(test)]
+fn assert_clone<T: Clone>() {}
 
 #[test]
 fn check_error_send() {
@@ -22,6 +24,17 @@ fn check_error_display() {
     assert_display::<libloading::Error>();
 }
 
+#[test]
+fn check_error_clone() {
+    assert_clone::<libloading::Error>();
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn check_lazy_library_sync() {
+    assert_sync::<libloading::LazyLibrary>();
+}
+
 #[test]
 fn check_library_send() {
     assert_send::<libloading::Library>();
@@ -44,6 +57,26 @@ fn check_library_sync() {
     assert_sync::<libloading::Library>();
 }
 
+#[cfg(feature = "alloc")]
+#[test]
+fn check_static_library_send_sync() {
+    assert_send::<libloading::StaticLibrary>();
+    assert_sync::<libloading::StaticLibrary>();
+}
+
+#[test]
+fn check_symbol_name_send_sync() {
+    assert_send::<libloading::SymbolName>();
+    assert_sync::<libloading::SymbolName>();
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn check_filename_send_sync() {
+    assert_send::<libloading::Filename>();
+    assert_sync::<libloading::Filename>();
+}
+
 #[cfg(unix)]
 #[test]
 fn check_unix_library_sync() {
@@ -0,0 +1,98 @@
+#![cfg(all(feature = "mock", unix))]
+use super::functions::{lib_path, make_helpers};
+use libloading::mock::{self, MockLibrary};
+use libloading::{Error, Library, Symbol};
+use std::error::Error as _;
+
+extern "C" fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+static COUNTER: u32 = 7;
+
+fn source(error: &Error) -> String {
+    error.source().unwrap().to_string()
+}
+
+#[test]
+fn mock_symbols() {
+    mock::register(
+        "libmock_symbols.so",
+        MockLibrary::new()
+            .symbol("add", add as *const _)
+            .symbol(b"counter\0", &raw const COUNTER as *const _)
+            .missing_symbol("scripted", "scripted failure"),
+    );
+    unsafe {
+        let library = Library::new("libmock_symbols.so").unwrap();
+        assert_eq!(mock::open_count("libmock_symbols.so"), 1);
+        let f: Symbol<extern "C" fn(u32, u32) -> u32> = library.get("add").unwrap();
+        assert_eq!(f(2, 3), 5);
+        let counter: Symbol<*const u32> = library.get("counter").unwrap();
+        assert_eq!(**counter, 7);
+
+        let error = library.get::<extern "C" fn()>("scripted").unwrap_err();
+        assert!(matches!(error, Error::DlSym { .. }), "{error:?}");
+        assert_eq!(source(&error), "scripted failure");
+        let error = library.get::<extern "C" fn()>("unknown").unwrap_err();
+        assert_eq!(
+            source(&error),
+            "libmock_symbols.so: undefined symbol: unknown"
+        );
+        drop(library);
+    }
+    assert_eq!(mock::open_count("libmock_symbols.so"), 0);
+}
+
+#[test]
+fn mock_failures() {
+    mock::register(
+        "libmock_open.so",
+        MockLibrary::new().open_error("libmock_open.so: invalid ELF header"),
+    );
+    mock::register(
+        "libmock_close.so",
+        MockLibrary::new().close_error("cannot close"),
+    );
+    unsafe {
+        let error = Library::new("libmock_open.so").unwrap_err();
+        assert!(matches!(error, Error::DlOpen { .. }), "{error:?}");
+        assert_eq!(source(&error), "libmock_open.so: invalid ELF header");
+
+        let error = Library::new("libmock_close.so")
+            .unwrap()
+            .close()
+            .unwrap_err();
+        assert!(matches!(error, Error::DlClose { .. }), "{error:?}");
+        assert_eq!(source(&error), "cannot close");
+        assert_eq!(mock::open_count("libmock_close.so"), 0);
+
+        #[cfg(target_os = "linux")]
+        {
+            let library = libloading::os::unix::Library::new("libmock_close.so").unwrap();
+            let error = library.pin().unwrap_err();
+            assert!(matches!(error, Error::DlInfo { .. }), "{error:?}");
+        }
+    }
+}
+
+#[test]
+fn mock_unregistered() {
+    make_helpers();
+    mock::register("libmock_unregistered.so", MockLibrary::new());
+    assert!(mock::unregister("libmock_unregistered.so"));
+    assert!(!mock::unregister("libmock_unregistered.so"));
+    unsafe {
+        let error = Library::new("libmock_unregistered.so").unwrap_err();
+        assert!(matches!(error, Error::DlOpen { .. }), "{error:?}");
+        // Real libraries are still loaded by the system loader.
+        let library = Library::new(lib_path()).unwrap();
+        let f: Symbol<extern "C" fn(u32) -> u32> = library.get("test_identity_u32").unwrap();
+        assert_eq!(f(42), 42);
+        let error = library
+            .get::<extern "C" fn()>("test_does_not_exist")
+            .unwrap_err();
+        assert!(matches!(error, Error::DlSym { .. }), "{error:?}");
+        library.close().unwrap();
+    }
+}
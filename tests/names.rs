@@ -0,0 +1,52 @@
+#![cfg(feature = "std")]
+use super::functions::{lib_path, make_helpers};
+use libloading::{symbol, Error, Filename, Library, StaticLibrary, Symbol, SymbolName};
+
+extern "C" fn double(value: u32) -> u32 {
+    value * 2
+}
+
+#[test]
+fn symbol_name() {
+    const DOUBLE: &SymbolName = symbol!("double");
+    let name = SymbolName::new("double").unwrap();
+    assert_eq!(name, *DOUBLE);
+    assert_eq!(SymbolName::new(b"double\0").unwrap(), name);
+    assert_eq!(SymbolName::from_static(c"double"), name);
+    assert_eq!(name.as_c_str(), c"double");
+
+    let library = StaticLibrary::new().with_symbol(DOUBLE, double as *const _);
+    unsafe {
+        let f: Symbol<extern "C" fn(u32) -> u32> = library.get(&name).unwrap();
+        assert_eq!(f(21), 42);
+        let f: Symbol<extern "C" fn(u32) -> u32> = library.get(symbol!("double")).unwrap();
+        assert_eq!(f(21), 42);
+    }
+
+    let error = SymbolName::new("dou\0ble").unwrap_err();
+    assert!(matches!(error, Error::InteriorZeroElements), "{error:?}");
+}
+
+#[test]
+fn filename() {
+    make_helpers();
+    let filename = Filename::new(lib_path()).unwrap();
+    assert_eq!(filename, Filename::new(lib_path().as_os_str()).unwrap());
+    #[cfg(unix)]
+    assert_eq!(
+        filename.as_c_str().to_bytes(),
+        lib_path().as_os_str().as_encoded_bytes()
+    );
+    #[cfg(windows)]
+    assert_eq!(
+        String::from_utf16(filename.as_wide()).unwrap(),
+        lib_path().to_str().unwrap()
+    );
+    let library = unsafe { Library::new(&filename) }.unwrap();
+    let f: Symbol<extern "C" fn(u32) -> u32> =
+        unsafe { library.get(symbol!("test_identity_u32")) }.unwrap();
+    assert_eq!(f(42), 42);
+
+    let error = Filename::new("lib\0rary.so").unwrap_err();
+    assert!(matches!(error, Error::InteriorZeroElements), "{error:?}");
+}
@@ -0,0 +1,26 @@
+#![cfg(all(not(feature = "alloc"), unix))]
+use libloading::{Error, Library};
+use std::error::Error as _;
+
+#[test]
+fn name_too_long() {
+    let name = "x".repeat(5000);
+    let error = unsafe { Library::new(name.as_str()) }.unwrap_err();
+    assert!(
+        matches!(error, Error::NameTooLong { maximum: 1023 }),
+        "{error:?}"
+    );
+    let library = libloading::os::unix::Library::this();
+    let error = unsafe { library.get::<*const u8>(name.as_bytes()) }.unwrap_err();
+    assert!(matches!(error, Error::NameTooLong { .. }), "{error:?}");
+}
+
+#[test]
+fn error_message_truncated() {
+    let name = format!("/nonexistent/{}.so", "x".repeat(1000));
+    let error = unsafe { Library::new(name.as_str()) }.unwrap_err();
+    assert!(matches!(error, Error::DlOpen { .. }), "{error:?}");
+    let message = error.source().unwrap().to_string();
+    // The message contains the filename, but only fits partially in the inline buffer.
+    assert_eq!(message.len(), 255, "{message}");
+}
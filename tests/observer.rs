@@ -0,0 +1,134 @@
+#![cfg(feature = "std")]
+use super::functions::{lib_path, make_helpers};
+use libloading::observer::{add_observer, LibraryName, LoadObserver, SymbolKey};
+use libloading::{Error, Library, Symbol};
+use std::ffi::c_void;
+use std::sync::Mutex;
+
+/// Records the events for the libraries with `observed` in their name, because the observer sees
+/// all the libraries loaded by the tests running concurrently.
+struct Recorder {
+    events: Mutex<Vec<String>>,
+    library: Mutex<Option<usize>>,
+}
+
+static RECORDER: Recorder = Recorder {
+    events: Mutex::new(Vec::new()),
+    library: Mutex::new(None),
+};
+
+impl Recorder {
+    fn is_observed(&self, library: *mut c_void) -> bool {
+        *self.library.lock().unwrap() == Some(library as usize)
+    }
+
+    fn record(&self, event: String) {
+        self.events.lock().unwrap().push(event);
+    }
+
+    fn take(&self) -> Vec<String> {
+        std::mem::take(&mut *self.events.lock().unwrap())
+    }
+}
+
+fn observed(name: LibraryName<'_>) -> bool {
+    name.to_string().contains("observed")
+}
+
+impl LoadObserver for Recorder {
+    fn before_load(&self, name: LibraryName<'_>) {
+        if observed(name) {
+            self.record("before_load".into());
+        }
+    }
+
+    fn after_load(&self, name: LibraryName<'_>, result: Result<*mut c_void, &Error>) {
+        if observed(name) {
+            if let Ok(library) = result {
+                *self.library.lock().unwrap() = Some(library as usize);
+            }
+            self.record(format!("after_load {}", result.is_ok()));
+        }
+    }
+
+    fn after_get(
+        &self,
+        library: *mut c_void,
+        symbol: SymbolKey<'_>,
+        result: Result<*mut c_void, &Error>,
+    ) {
+        if self.is_observed(library) {
+            let SymbolKey::Name(name) = symbol else {
+                panic!("{symbol:?}");
+            };
+            self.record(format!(
+                "after_get {} {}",
+                name.to_str().unwrap(),
+                result.is_ok()
+            ));
+        }
+    }
+
+    fn after_close(&self, library: *mut c_void, result: Result<(), &Error>) {
+        if self.is_observed(library) {
+            *self.library.lock().unwrap() = None;
+            self.record(format!("after_close {}", result.is_ok()));
+        }
+    }
+
+    fn after_drop(&self, library: *mut c_void, result: Result<(), &Error>) {
+        if self.is_observed(library) {
+            *self.library.lock().unwrap() = None;
+            self.record(format!("after_drop {}", result.is_ok()));
+        }
+    }
+}
+
+#[test]
+fn observer_events() {
+    make_helpers();
+    let path = lib_path().with_file_name("libtest_helpers_observed.module");
+    std::fs::copy(lib_path(), &path).unwrap();
+    add_observer(&RECORDER);
+    unsafe {
+        let library = Library::new(&path).unwrap();
+        let _: Symbol<extern "C" fn(u32) -> u32> = library.get("test_identity_u32").unwrap();
+        library
+            .get::<extern "C" fn()>("test_does_not_exist")
+            .unwrap_err();
+        // Pinning does not report the reference it opens and closes internally.
+        #[cfg(target_os = "linux")]
+        let library = {
+            let library = libloading::os::unix::Library::from(library);
+            library.pin().unwrap();
+            Library::from(library)
+        };
+        drop(library);
+        Library::new(&path).unwrap().close().unwrap();
+        Library::new("/nonexistent/libtest_observed.module").unwrap_err();
+    }
+    assert_eq!(
+        RECORDER.take(),
+        [
+            "before_load",
+            "after_load true",
+            "after_get test_identity_u32 true",
+            "after_get test_does_not_exist false",
+            "after_drop true",
+            "before_load",
+            "after_load true",
+            "after_close true",
+            "before_load",
+            "after_load false",
+        ]
+    );
+}
+
+#[test]
+fn library_name_display() {
+    let posix = LibraryName::Posix(c"lib\xffname.so");
+    assert_eq!(posix.to_string(), "lib\u{fffd}name.so");
+    let windows: Vec<u16> = "name.dll".encode_utf16().collect();
+    assert_eq!(LibraryName::Windows(&windows).to_string(), "name.dll");
+    assert_eq!(LibraryName::This.to_string(), "<this program>");
+}
@@ -0,0 +1,137 @@
+#![cfg(feature = "std")]
+use super::functions::{lib_path, make_helpers};
+use libloading::plugin::PluginManager;
+use libloading::{library_filename, Error};
+use std::path::PathBuf;
+
+/// Create a directory with copies of the test helpers as the plugins `names`, as well as a file
+/// that is not a library and one that is not a valid library.
+fn plugin_dir(dir: &str, names: &[&str]) -> PathBuf {
+    make_helpers();
+    let dir = lib_path().with_file_name(dir);
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    for name in names {
+        std::fs::copy(lib_path(), dir.join(library_filename(name))).unwrap();
+    }
+    std::fs::write(dir.join("README.txt"), "not a plugin").unwrap();
+    std::fs::write(dir.join(library_filename("broken")), "not a library").unwrap();
+    dir
+}
+
+#[test]
+fn load_all_in_order() {
+    let dir = plugin_dir("plugins_in_order", &["gamma", "alpha", "beta"]);
+    let mut manager = PluginManager::new(&dir).init_symbol("test_plugin_init");
+    let results = unsafe { manager.load_all() }.unwrap();
+    let attempted: Vec<_> = results.iter().map(|(path, _)| path.clone()).collect();
+    let expected: Vec<_> = ["alpha", "beta", "broken", "gamma"]
+        .iter()
+        .map(|name| dir.join(library_filename(name)))
+        .collect();
+    assert_eq!(attempted, expected);
+    assert!(results[0].1.is_ok());
+    assert!(results[1].1.is_ok());
+    assert!(results[2].1.is_err());
+    assert!(results[3].1.is_ok());
+
+    let names: Vec<_> = manager
+        .plugins()
+        .iter()
+        .map(|p| p.name().to_owned())
+        .collect();
+    assert_eq!(names, ["alpha", "beta", "gamma"]);
+    assert!(manager.get("beta").is_some());
+    assert!(manager.get("broken").is_none());
+
+    // Plugins that are already loaded are not attempted again.
+    let results = unsafe { manager.load_all() }.unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].0, dir.join(library_filename("broken")));
+
+    let closed = manager.close();
+    let closed: Vec<_> = closed
+        .into_iter()
+        .map(|(path, result)| {
+            result.unwrap();
+            path
+        })
+        .collect();
+    let mut expected = vec![
+        expected[0].clone(),
+        expected[1].clone(),
+        expected[3].clone(),
+    ];
+    expected.reverse();
+    assert_eq!(closed, expected);
+}
+
+#[test]
+fn init_failure() {
+    let dir = plugin_dir("plugins_init_failure", &["alpha"]);
+    let mut manager = PluginManager::new(&dir).init_symbol("test_plugin_init_failing");
+    let results = unsafe { manager.load_all() }.unwrap();
+    let alpha = results
+        .iter()
+        .find(|(path, _)| path.ends_with(library_filename("alpha")))
+        .unwrap();
+    assert!(matches!(alpha.1, Err(Error::PluginInit { status: 7 })));
+    assert!(manager.plugins().is_empty());
+
+    let mut manager = PluginManager::new(&dir).init_symbol("test_plugin_no_such_init");
+    let results = unsafe { manager.load_all() }.unwrap();
+    assert!(results.iter().all(|(_, result)| result.is_err()));
+}
+
+#[test]
+fn reload() {
+    let dir = plugin_dir("plugins_reload", &["alpha", "beta"]);
+    let mut manager = PluginManager::new(&dir);
+    unsafe {
+        manager.reload("beta").unwrap();
+        manager.load_all().unwrap();
+        manager.reload("beta").unwrap();
+        assert!(manager.reload("missing").is_err());
+    }
+    let names: Vec<_> = manager
+        .plugins()
+        .iter()
+        .map(|p| p.name().to_owned())
+        .collect();
+    assert_eq!(names, ["beta", "alpha"]);
+    unsafe {
+        let f = manager
+            .get("beta")
+            .unwrap()
+            .library()
+            .get::<unsafe extern "C" fn(u32) -> u32>(b"test_identity_u32\0")
+            .unwrap();
+        assert_eq!(f(42), 42);
+    }
+}
+
+#[test]
+fn require_manifest() {
+    use libloading::manifest::AbiVersion;
+    let dir = plugin_dir("plugins_manifest", &[]);
+    std::fs::copy(
+        super::manifest::manifest_helpers_path(),
+        dir.join(library_filename("alpha")),
+    )
+    .unwrap();
+    let mut manager = PluginManager::new(&dir).require_manifest(AbiVersion::new(2, 0, 0));
+    let results = unsafe { manager.load_all() }.unwrap();
+    assert!(results.iter().all(|(_, result)| result.is_err()));
+    let mut manager = PluginManager::new(&dir).require_manifest(AbiVersion::new(1, 2, 0));
+    unsafe { manager.load_all() }.unwrap();
+    assert_eq!(manager.plugins().len(), 1);
+}
+
+#[test]
+fn missing_directory() {
+    let mut manager = PluginManager::new(lib_path().with_file_name("no_such_plugin_directory"));
+    assert!(matches!(
+        unsafe { manager.load_all() },
+        Err(Error::Io { .. })
+    ));
+}
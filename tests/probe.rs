@@ -0,0 +1,122 @@
+#![cfg(all(
+    feature = "std",
+    any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "freebsd",
+        target_os = "dragonfly",
+        target_os = "openbsd",
+        target_os = "netbsd"
+    )
+))]
+use super::functions::{lib_path, make_helpers};
+use libloading::os::unix::{probe_load, probe_load_with_symbols, ProbeOutcome};
+use libloading::Error;
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Build a library that runs `constructor` when it is loaded.
+#[cfg(target_os = "linux")]
+fn constructor_library(name: &str, constructor: &str) -> std::path::PathBuf {
+    let path = lib_path().with_file_name(format!("libtest_probe_{name}.module"));
+    let source = lib_path().with_file_name(format!("test_probe_{name}.rs"));
+    std::fs::write(
+        &source,
+        format!(
+            "#[used] #[link_section = \".init_array\"] \
+             static CONSTRUCTOR: extern \"C\" fn() = constructor; \
+             extern \"C\" fn constructor() {{ {constructor} }}"
+        ),
+    )
+    .unwrap();
+    let rustc = std::env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    let status = std::process::Command::new(rustc)
+        .arg("--crate-type=cdylib")
+        .arg(&source)
+        .arg("-o")
+        .arg(&path)
+        .status()
+        .expect("could not compile the probed library!");
+    assert!(status.success());
+    path
+}
+
+#[test]
+fn probe_helpers() {
+    make_helpers();
+    let outcome = probe_load(lib_path(), TIMEOUT).unwrap();
+    assert!(outcome.is_loaded(), "{outcome:?}");
+}
+
+#[test]
+fn probe_helpers_symbols() {
+    make_helpers();
+    let outcome =
+        probe_load_with_symbols(lib_path(), ["test_identity_u32", "TEST_TABLE"], TIMEOUT).unwrap();
+    assert!(outcome.is_loaded(), "{outcome:?}");
+    let outcome = probe_load_with_symbols(
+        lib_path(),
+        ["test_identity_u32", "test_does_not_exist"],
+        TIMEOUT,
+    )
+    .unwrap();
+    assert!(
+        matches!(
+            outcome,
+            ProbeOutcome::SymbolNotFound {
+                index: 1,
+                error: Error::DlSym { .. }
+            }
+        ),
+        "{outcome:?}"
+    );
+}
+
+#[test]
+fn probe_missing_library() {
+    let outcome = probe_load("/nonexistent/libtest_probe.module", TIMEOUT).unwrap();
+    assert!(
+        matches!(
+            outcome,
+            ProbeOutcome::LoadFailed {
+                error: Error::DlOpen { .. }
+            }
+        ),
+        "{outcome:?}"
+    );
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn probe_crashing_constructor() {
+    let path = constructor_library("crash", "std::process::abort();");
+    let outcome = probe_load(&path, TIMEOUT).unwrap();
+    assert!(
+        matches!(outcome, ProbeOutcome::Signaled { signal: 6 }),
+        "{outcome:?}"
+    );
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn probe_exiting_constructor() {
+    let path = constructor_library(
+        "exit",
+        "unsafe { _exit(3) } extern \"C\" { fn _exit(status: i32) -> !; }",
+    );
+    let outcome = probe_load(&path, TIMEOUT).unwrap();
+    assert!(
+        matches!(outcome, ProbeOutcome::Exited { code: 3 }),
+        "{outcome:?}"
+    );
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn probe_hanging_constructor() {
+    let path = constructor_library("hang", "loop { std::thread::park(); }");
+    let outcome = probe_load(&path, Duration::from_millis(200)).unwrap();
+    assert!(matches!(outcome, ProbeOutcome::TimedOut), "{outcome:?}");
+}
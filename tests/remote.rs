@@ -0,0 +1,126 @@
+#![cfg(feature = "remote")]
+use super::functions::{lib_path, make_helpers};
+use libloading::remote::{Argument, RemoteLibrary, ReturnType, Value};
+use libloading::Error;
+use std::process::Command;
+
+fn host() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_libloading-host"))
+}
+
+#[test]
+fn remote_call() {
+    make_helpers();
+    let mut library =
+        RemoteLibrary::new(env!("CARGO_BIN_EXE_libloading-host"), lib_path()).unwrap();
+    let identity = library.get("test_identity_u32").unwrap();
+    let value = library
+        .call(identity, &mut [Argument::U32(0xdead_beef)], ReturnType::U32)
+        .unwrap();
+    assert_eq!(value, Value::U32(0xdead_beef));
+
+    let sum = library.get("test_sum_mixed").unwrap();
+    let mut arguments = [
+        Argument::I32(1),
+        Argument::F64(0.5),
+        Argument::I64(1 << 40),
+        Argument::F32(0.25),
+    ];
+    let value = library.call(sum, &mut arguments, ReturnType::F64).unwrap();
+    assert_eq!(value, Value::F64((1u64 << 40) as f64 + 1.75));
+    library.close().unwrap();
+}
+
+#[test]
+fn remote_call_bytes() {
+    make_helpers();
+    let mut library = RemoteLibrary::with_host(host(), lib_path()).unwrap();
+    let fill = library.get("test_fill_bytes").unwrap();
+    let mut arguments = [
+        Argument::Bytes(vec![0; 8]),
+        Argument::Usize(5),
+        Argument::U32(0x2a),
+    ];
+    let value = library
+        .call(fill, &mut arguments, ReturnType::Usize)
+        .unwrap();
+    assert_eq!(value, Value::Usize(5));
+    assert_eq!(
+        arguments[0],
+        Argument::Bytes(vec![42, 42, 42, 42, 42, 0, 0, 0])
+    );
+}
+
+// The C library is a dependency of the test helpers, so `write` is found in them.
+#[cfg(unix)]
+#[test]
+fn remote_stdout() {
+    make_helpers();
+    let mut host = host();
+    host.stdout(std::process::Stdio::null());
+    let mut library = RemoteLibrary::with_host(host, lib_path()).unwrap();
+    let write = library.get("write").unwrap();
+    let mut arguments = [
+        Argument::I32(1),
+        Argument::Bytes(b"output".to_vec()),
+        Argument::Usize(6),
+    ];
+    let value = library
+        .call(write, &mut arguments, ReturnType::Usize)
+        .unwrap();
+    assert_eq!(value, Value::Usize(6));
+    // The output has not been mistaken for a response.
+    let identity = library.get("test_identity_u32").unwrap();
+    let value = library
+        .call(identity, &mut [Argument::U32(7)], ReturnType::U32)
+        .unwrap();
+    assert_eq!(value, Value::U32(7));
+    library.close().unwrap();
+}
+
+#[test]
+fn remote_errors() {
+    make_helpers();
+    let error = RemoteLibrary::with_host(host(), "/nonexistent/libtest_remote.module").unwrap_err();
+    assert!(
+        matches!(error, Error::DlOpen { .. } | Error::LoadLibraryExW { .. }),
+        "{error:?}"
+    );
+    let mut library = RemoteLibrary::with_host(host(), lib_path()).unwrap();
+    let error = library.get("test_does_not_exist").unwrap_err();
+    assert!(
+        matches!(error, Error::DlSym { .. } | Error::GetProcAddress { .. }),
+        "{error:?}"
+    );
+    let identity = library.get("test_identity_u32").unwrap();
+    let error = library
+        .call(identity, &mut vec![Argument::I32(0); 5], ReturnType::I32)
+        .unwrap_err();
+    assert!(matches!(error, Error::Remote { .. }), "{error:?}");
+    // The host is still usable after an error.
+    let value = library
+        .call(identity, &mut [Argument::I32(7)], ReturnType::I32)
+        .unwrap();
+    assert_eq!(value, Value::I32(7));
+}
+
+#[test]
+fn remote_crash() {
+    make_helpers();
+    let mut host = host();
+    host.stderr(std::process::Stdio::null());
+    let mut library = RemoteLibrary::with_host(host, lib_path()).unwrap();
+    let fill = library.get("test_fill_bytes").unwrap();
+    // Writing to a null pointer crashes the host, but not this process.
+    let mut arguments = [Argument::Usize(0), Argument::Usize(1), Argument::U32(0)];
+    let error = library
+        .call(fill, &mut arguments, ReturnType::Usize)
+        .unwrap_err();
+    let Error::HostExited { status } = error else {
+        panic!("{error:?}");
+    };
+    assert!(!status.success());
+    let error = library.get("test_identity_u32").unwrap_err();
+    assert!(matches!(error, Error::HostExited { .. }), "{error:?}");
+    assert!(library.close().is_err());
+}
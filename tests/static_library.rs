@@ -0,0 +1,61 @@
+#![cfg(feature = "alloc")]
+use libloading::{Error, StaticLibrary, Symbol};
+
+extern "C" fn identity(value: u32) -> u32 {
+    value
+}
+
+extern "C" fn double(value: u32) -> u32 {
+    value * 2
+}
+
+static VALUE: u32 = 7;
+
+#[test]
+fn static_library_get() {
+    let library = StaticLibrary::new()
+        .with_symbol("double", double as *const _)
+        .with_symbol(b"value\0", &raw const VALUE as *const _);
+    unsafe {
+        let f: Symbol<extern "C" fn(u32) -> u32> = library.get("double").unwrap();
+        assert_eq!(f(21), 42);
+        let value: Symbol<*const u32> = library.get("value").unwrap();
+        assert_eq!(**value, 7);
+
+        let error = library.get::<extern "C" fn()>("missing").unwrap_err();
+        let Error::StaticSymbolNotFound { name } = error else {
+            panic!("{error:?}");
+        };
+        assert_eq!(name.as_bytes(), b"missing");
+        let error = library.get::<u8>("value").unwrap_err();
+        assert!(matches!(error, Error::IncompatibleSize), "{error:?}");
+    }
+}
+
+#[test]
+fn static_library_insert() {
+    let mut library = StaticLibrary::new();
+    assert_eq!(library.insert("f", identity as *const _), None);
+    assert_eq!(
+        library.insert("f", double as *const _),
+        Some(identity as *const _)
+    );
+    let f: Symbol<extern "C" fn(u32) -> u32> = unsafe { library.get("f") }.unwrap();
+    assert_eq!(f(2), 4);
+}
+
+#[test]
+fn static_library_long_names() {
+    // Names around and past the size of the buffer on the stack names are terminated in.
+    for length in [1, 382, 383, 384, 385, 1000, 10000] {
+        let name = "x".repeat(length);
+        let library = StaticLibrary::new().with_symbol(&name, double as *const _);
+        let f: Symbol<extern "C" fn(u32) -> u32> = unsafe { library.get(name.as_bytes()) }.unwrap();
+        assert_eq!(f(21), 42);
+        let error = unsafe { library.get::<extern "C" fn()>(&name[1..]) }.unwrap_err();
+        let Error::StaticSymbolNotFound { name: missing } = error else {
+            panic!("{error:?}");
+        };
+        assert_eq!(missing.as_bytes(), &name.as_bytes()[1..]);
+    }
+}
@@ -0,0 +1,65 @@
+#![cfg(feature = "std")]
+use super::functions::{lib_path, make_helpers};
+use libloading::{Error, Library, StaticLibrary, SymbolSource};
+
+extern "C" fn identity(value: u32) -> u32 {
+    value
+}
+
+/// A table of the functions of a library, loaded the same way from any `SymbolSource`.
+struct Functions<'lib, L: SymbolSource + 'lib> {
+    identity: L::Symbol<'lib, extern "C" fn(u32) -> u32>,
+    optional: Option<L::Symbol<'lib, extern "C" fn()>>,
+}
+
+impl<'lib, L: SymbolSource> Functions<'lib, L> {
+    fn load(library: &'lib L) -> Result<Self, Error> {
+        unsafe {
+            Ok(Functions {
+                identity: library.get("test_identity_u32")?,
+                optional: library.get("test_does_not_exist").ok(),
+            })
+        }
+    }
+}
+
+fn check<L: SymbolSource>(library: &L) {
+    let functions = Functions::load(library).unwrap();
+    assert_eq!((functions.identity)(42), 42);
+    assert!(functions.optional.is_none());
+    let f = unsafe { library.get_singlethreaded::<extern "C" fn(u32) -> u32>("test_identity_u32") }
+        .unwrap();
+    assert_eq!(f(7), 7);
+}
+
+#[test]
+fn symbol_source_library() {
+    make_helpers();
+    let library = unsafe { Library::new(lib_path()) }.unwrap();
+    check(&library);
+    let handle = library.handle();
+    #[cfg(unix)]
+    let library = libloading::os::unix::Library::from(library);
+    #[cfg(windows)]
+    let library = libloading::os::windows::Library::from(library);
+    assert_eq!(library.handle(), handle);
+    check(&library);
+    let raw = library.into_raw();
+    assert_eq!(raw, handle);
+    #[cfg(unix)]
+    let library = unsafe { libloading::os::unix::Library::from_raw(raw) };
+    #[cfg(windows)]
+    let library = unsafe { libloading::os::windows::Library::from_raw(raw) };
+    library.close().unwrap();
+}
+
+#[test]
+fn symbol_source_static_library() {
+    let library = StaticLibrary::new()
+        .with_symbol("test_identity_u32", identity as *const _)
+        .with_symbol("null", std::ptr::null());
+    check(&library);
+    assert_eq!(library.handle(), &library as *const _);
+    let null = unsafe { library.get_singlethreaded::<*const u8>("null") }.unwrap();
+    assert!(null.is_null());
+}
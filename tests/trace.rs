@@ -0,0 +1,125 @@
+#![cfg(all(feature = "tracing", feature = "std"))]
+use super::functions::{lib_path, make_helpers};
+use libloading::Library;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+/// Records every span and event as a line of text.
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<String>>>);
+
+struct Fields<'a>(&'a mut String);
+
+impl Visit for Fields<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        write!(self.0, " {}={:?}", field.name(), value).unwrap();
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut line = format!("span {}", span.metadata().name());
+        span.record(&mut Fields(&mut line));
+        let mut lines = self.0.lock().unwrap();
+        lines.push(line);
+        Id::from_u64(lines.len() as u64)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        let mut lines = self.0.lock().unwrap();
+        let line = &mut lines[span.into_u64() as usize - 1];
+        values.record(&mut Fields(line));
+    }
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut line = String::from("event");
+        event.record(&mut Fields(&mut line));
+        self.0.lock().unwrap().push(line);
+    }
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+#[test]
+fn tracing_load_get_close() {
+    make_helpers();
+    let recorder = Recorder::default();
+    tracing::subscriber::with_default(recorder.clone(), || unsafe {
+        let library = Library::new(lib_path()).unwrap();
+        library.get::<extern "C" fn()>("test_identity_u32").unwrap();
+        library
+            .get::<extern "C" fn()>("test_does_not_exist")
+            .unwrap_err();
+        library.close().unwrap();
+    });
+    let lines = recorder.0.lock().unwrap();
+    let filename = format!("filename={:?}", lib_path());
+    #[cfg(unix)]
+    let (load, get, close) = ("span dlopen", "span dlsym", "span dlclose");
+    #[cfg(windows)]
+    let (load, get, close) = (
+        "span LoadLibraryExW",
+        "span GetProcAddress",
+        "span FreeLibrary",
+    );
+    assert!(lines[0].starts_with(load), "{lines:#?}");
+    assert!(lines[0].contains(&filename), "{lines:#?}");
+    assert!(lines[0].contains("elapsed="), "{lines:#?}");
+    assert_eq!(lines[1], "event message=succeeded");
+    assert!(lines[2].starts_with(get), "{lines:#?}");
+    assert!(
+        lines[2].contains("symbol=\"test_identity_u32\""),
+        "{lines:#?}"
+    );
+    assert_eq!(lines[3], "event message=succeeded");
+    assert!(
+        lines[4].contains("symbol=\"test_does_not_exist\""),
+        "{lines:#?}"
+    );
+    assert!(
+        lines[5].starts_with("event message=failed error="),
+        "{lines:#?}"
+    );
+    assert!(lines[6].starts_with(close), "{lines:#?}");
+    assert_eq!(lines[7], "event message=succeeded");
+    assert_eq!(lines.len(), 8, "{lines:#?}");
+}
+
+#[test]
+fn tracing_drop_without_observer() {
+    // The other tests register observers for the rest of the process, so check in a process
+    // running only this test.
+    if std::env::var_os("LIBLOADING_TEST_UNOBSERVED").is_none() {
+        let status = std::process::Command::new(std::env::current_exe().unwrap())
+            .args(["--exact", "trace::tracing_drop_without_observer"])
+            .env("LIBLOADING_TEST_UNOBSERVED", "1")
+            .status()
+            .unwrap();
+        assert!(status.success());
+        return;
+    }
+    make_helpers();
+    let recorder = Recorder::default();
+    tracing::subscriber::with_default(recorder.clone(), || unsafe {
+        drop(Library::new(lib_path()).unwrap());
+    });
+    let lines = recorder.0.lock().unwrap();
+    #[cfg(unix)]
+    let close = "span dlclose";
+    #[cfg(windows)]
+    let close = "span FreeLibrary";
+    assert!(lines[2].starts_with(close), "{lines:#?}");
+    assert_eq!(lines[3], "event message=succeeded");
+    assert_eq!(lines.len(), 4, "{lines:#?}");
+}
@@ -0,0 +1,65 @@
+#![cfg(feature = "std")]
+use super::functions::{lib_path, make_helpers};
+use libloading::{Error, Library, PoisonableLibrary, Symbol};
+
+type CallUnwind = extern "C-unwind" fn(extern "C-unwind" fn(u32) -> u32, u32) -> u32;
+
+// A panic can only be caught if it has been raised by the same copy of the standard library, so
+// the panics here are raised by the tests and unwind through the test helpers.
+extern "C-unwind" fn panic_on_zero(x: u32) -> u32 {
+    if x == 0 {
+        panic!("zero");
+    }
+    x
+}
+
+extern "C-unwind" fn panic_with_string(x: u32) -> u32 {
+    panic!("got {x}");
+}
+
+#[test]
+fn call_catch_unwind() {
+    make_helpers();
+    unsafe {
+        let lib = Library::new(lib_path()).unwrap();
+        let call: Symbol<CallUnwind> = lib.get(b"test_call_unwind\0").unwrap();
+        assert_eq!(call.call_catch_unwind((panic_on_zero, 1)).unwrap(), 2);
+        assert!(matches!(
+            call.call_catch_unwind((panic_on_zero, 0)),
+            Err(Error::Panicked { message }) if message == "zero"
+        ));
+        assert!(matches!(
+            call.call_catch_unwind((panic_with_string, 3)),
+            Err(Error::Panicked { message }) if message == "got 3"
+        ));
+        assert_eq!(call.call_catch_unwind((panic_on_zero, 1)).unwrap(), 2);
+    }
+}
+
+#[test]
+fn poisonable_library() {
+    make_helpers();
+    unsafe {
+        let lib = PoisonableLibrary::new(Library::new(lib_path()).unwrap());
+        let call: Symbol<CallUnwind> = lib.library().get(b"test_call_unwind\0").unwrap();
+        let identity: Symbol<extern "C-unwind" fn(u32) -> u32> =
+            lib.library().get(b"test_identity_u32\0").unwrap();
+        assert_eq!(lib.call_catch_unwind(&call, (panic_on_zero, 1)).unwrap(), 2);
+        assert!(!lib.is_poisoned());
+        assert!(matches!(
+            lib.call_catch_unwind(&call, (panic_on_zero, 0)),
+            Err(Error::Panicked { .. })
+        ));
+        assert!(lib.is_poisoned());
+        assert!(matches!(
+            lib.call_catch_unwind(&call, (panic_on_zero, 1)),
+            Err(Error::Poisoned)
+        ));
+        assert!(matches!(
+            lib.call_catch_unwind(&identity, (1,)),
+            Err(Error::Poisoned)
+        ));
+        lib.clear_poison();
+        assert_eq!(lib.call_catch_unwind(&identity, (1,)).unwrap(), 1);
+    }
+}